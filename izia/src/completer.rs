@@ -0,0 +1,85 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use linefeed::{Completer, Completion, Prompter, Terminal};
+use std::sync::RwLock;
+use zia::Context;
+
+/// Characters that separate the labels of an expression.
+pub const WORD_BREAK_CHARS: &str = " \t\n()";
+
+/// Completes words with the labels of a context, including the built-in ones.
+#[derive(Default)]
+pub struct LabelCompleter {
+    labels: RwLock<Vec<String>>,
+}
+
+impl LabelCompleter {
+    /// Replaces the labels that can be completed with those of `context`. This should be called after each command as commands may add or remove labels.
+    pub fn update(&self, context: &Context) {
+        *self.labels.write().unwrap() = context.labels();
+    }
+
+    /// The labels that start with `word`, in alphabetical order.
+    fn matching(&self, word: &str) -> Vec<String> {
+        self.labels
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|label| label.starts_with(word))
+            .cloned()
+            .collect()
+    }
+}
+
+impl<Term: Terminal> Completer<Term> for LabelCompleter {
+    fn complete(
+        &self,
+        word: &str,
+        _prompter: &Prompter<Term>,
+        _start: usize,
+        _end: usize,
+    ) -> Option<Vec<Completion>> {
+        let completions: Vec<Completion> =
+            self.matching(word).into_iter().map(Completion::simple).collect();
+        if completions.is_empty() {
+            None
+        } else {
+            Some(completions)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LabelCompleter;
+    use zia::Context;
+
+    #[test]
+    fn prefixes_complete_to_user_defined_and_built_in_labels() {
+        let mut context = Context::new_without_logging();
+        assert_eq!(context.execute("let dew -> deep"), "");
+        assert_eq!(context.execute("let ode -> ad"), "");
+        let completer = LabelCompleter::default();
+        completer.update(&context);
+        assert_eq!(
+            completer.matching("de"),
+            ["deep", "default", "definition_of", "dew"]
+        );
+        assert_eq!(completer.matching("dew"), ["dew"]);
+        assert!(completer.matching("x").is_empty());
+    }
+}
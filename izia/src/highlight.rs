@@ -0,0 +1,181 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use zia::{is_variable, Context};
+
+const RESET: &str = "\x1b[0m";
+const BUILT_IN: &str = "\x1b[1;35m";
+const KNOWN: &str = "\x1b[32m";
+/// Symbols that are not labels yet, so executing the command creates new concepts for them, which is usually a typo.
+const UNKNOWN: &str = "\x1b[4;33m";
+const VARIABLE: &str = "\x1b[36m";
+const UNMATCHED: &str = "\x1b[1;41m";
+/// The colours of parentheses by how deeply they are nested, so that each pair has the same colour.
+const PARENTHESES: [&str; 3] = ["\x1b[1;34m", "\x1b[1;33m", "\x1b[1;32m"];
+
+/// Colours the symbols of a command by what they label in a context.
+pub struct Highlighter {
+    concrete_labels: HashSet<String>,
+    labels: HashSet<String>,
+}
+
+impl Highlighter {
+    pub fn new(cont: &Context) -> Self {
+        Self {
+            concrete_labels: cont.concrete_labels().into_iter().collect(),
+            labels: cont.labels().into_iter().collect(),
+        }
+    }
+
+    /// Returns `line` with terminal colour codes added. `depth` is the number of parentheses left open by the previous lines of the command and is updated for the next line.
+    pub fn highlight(&self, line: &str, depth: &mut usize) -> String {
+        let mut highlighted = String::new();
+        let mut symbol = String::new();
+        for c in line.chars() {
+            if c == '(' || c == ')' || c.is_whitespace() {
+                highlighted += &self.highlight_symbol(&symbol);
+                symbol.clear();
+            }
+            match c {
+                '(' => {
+                    highlighted += &paint(PARENTHESES[*depth % 3], "(");
+                    *depth += 1;
+                },
+                ')' if *depth == 0 => highlighted += &paint(UNMATCHED, ")"),
+                ')' => {
+                    *depth -= 1;
+                    highlighted += &paint(PARENTHESES[*depth % 3], ")");
+                },
+                c if c.is_whitespace() => highlighted.push(c),
+                c => symbol.push(c),
+            }
+        }
+        highlighted + &self.highlight_symbol(&symbol)
+    }
+
+    fn highlight_symbol(&self, symbol: &str) -> String {
+        if symbol.is_empty() {
+            String::new()
+        } else if is_variable(symbol) {
+            // Variables used in rules are labelled too
+            paint(VARIABLE, symbol)
+        } else if self.concrete_labels.contains(symbol) {
+            paint(BUILT_IN, symbol)
+        } else if self.labels.contains(symbol) {
+            paint(KNOWN, symbol)
+        } else {
+            paint(UNKNOWN, symbol)
+        }
+    }
+}
+
+fn paint(colour: &str, text: &str) -> String {
+    format!("{}{}{}", colour, text, RESET)
+}
+
+/// Returns the number of rows of a terminal `columns` wide that `width` characters wrap onto.
+pub fn rows(width: usize, columns: usize) -> usize {
+    if columns == 0 {
+        1
+    } else {
+        width.div_ceil(columns).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlighter(commands: &[&str]) -> Highlighter {
+        let mut cont = Context::new_without_logging();
+        for command in commands {
+            assert_eq!(cont.execute(command), "");
+        }
+        Highlighter::new(&cont)
+    }
+
+    #[test]
+    fn symbols_are_coloured_by_what_they_label() {
+        let highlighter = highlighter(&["let (_x_ y) -> _x_"]);
+        assert_eq!(
+            highlighter.highlight("let _x_ y  z", &mut 0),
+            format!(
+                "{} {} {}  {}",
+                paint(BUILT_IN, "let"),
+                paint(VARIABLE, "_x_"),
+                paint(KNOWN, "y"),
+                paint(UNKNOWN, "z")
+            )
+        );
+    }
+
+    #[test]
+    fn parentheses_are_coloured_by_depth() {
+        let highlighter = highlighter(&[]);
+        assert_eq!(
+            highlighter.highlight("((a)) ()", &mut 0),
+            format!(
+                "{}{}{}{}{} {}{}",
+                paint(PARENTHESES[0], "("),
+                paint(PARENTHESES[1], "("),
+                paint(UNKNOWN, "a"),
+                paint(PARENTHESES[1], ")"),
+                paint(PARENTHESES[0], ")"),
+                paint(PARENTHESES[0], "("),
+                paint(PARENTHESES[0], ")")
+            )
+        );
+    }
+
+    #[test]
+    fn unmatched_parentheses_are_marked() {
+        let highlighter = highlighter(&[]);
+        assert_eq!(
+            highlighter.highlight("())", &mut 0),
+            format!(
+                "{}{}{}",
+                paint(PARENTHESES[0], "("),
+                paint(PARENTHESES[0], ")"),
+                paint(UNMATCHED, ")")
+            )
+        );
+    }
+
+    #[test]
+    fn depth_carries_over_to_the_next_line() {
+        let highlighter = highlighter(&[]);
+        let mut depth = 0;
+        assert_eq!(
+            highlighter.highlight("(let", &mut depth),
+            paint(PARENTHESES[0], "(") + &paint(BUILT_IN, "let")
+        );
+        assert_eq!(depth, 1);
+        assert_eq!(
+            highlighter.highlight(")", &mut depth),
+            paint(PARENTHESES[0], ")")
+        );
+        assert_eq!(depth, 0);
+    }
+
+    #[test]
+    fn wrapped_rows() {
+        assert_eq!(rows(0, 80), 1);
+        assert_eq!(rows(80, 80), 1);
+        assert_eq!(rows(81, 80), 2);
+        assert_eq!(rows(10, 0), 1);
+    }
+}
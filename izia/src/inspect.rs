@@ -0,0 +1,92 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use zia::{ConceptDetails, ConceptKind};
+
+/// Lays out the details of the concept of `expression` as a tree.
+pub fn render(expression: &str, details: &ConceptDetails) -> String {
+    let kind = match details.kind {
+        ConceptKind::Concrete => "concrete".to_string(),
+        ConceptKind::Abstract => "abstract".to_string(),
+        ConceptKind::String(ref string) => format!("string \"{}\"", string),
+    };
+    let branches = [
+        ("definition", optional(&details.definition)),
+        ("reduction", optional(&details.reduction)),
+        ("reduces from", list(&details.reduces_from)),
+        ("lefthand of", list(&details.lefthand_of)),
+        ("righthand of", list(&details.righthand_of)),
+        ("precedence", details.precedence.clone()),
+        ("associativity", details.associativity.clone()),
+        ("normal form", details.normal_form.clone()),
+    ];
+    let mut tree = format!("{} (concept {}, {})", expression, details.id, kind);
+    for (index, (name, value)) in branches.iter().enumerate() {
+        let branch = if index + 1 == branches.len() {
+            "└──"
+        } else {
+            "├──"
+        };
+        tree += &format!("\n{} {}: {}", branch, name, value);
+    }
+    tree
+}
+
+fn optional(syntax: &Option<String>) -> String {
+    syntax.clone().unwrap_or_else(|| "none".to_string())
+}
+
+fn list(syntax: &[String]) -> String {
+    if syntax.is_empty() {
+        "none".to_string()
+    } else {
+        syntax.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use zia::{ConceptDetails, ConceptKind};
+
+    #[test]
+    fn details_are_laid_out_as_a_tree() {
+        let details = ConceptDetails {
+            id: 66,
+            kind: ConceptKind::Abstract,
+            definition: Some("a b".to_string()),
+            reduction: Some("c".to_string()),
+            reduces_from: Vec::new(),
+            lefthand_of: vec!["d e".to_string(), "d f".to_string()],
+            righthand_of: Vec::new(),
+            precedence: "default".to_string(),
+            associativity: "right".to_string(),
+            normal_form: "c".to_string(),
+        };
+        assert_eq!(
+            render("d", &details),
+            "d (concept 66, abstract)
+├── definition: a b
+├── reduction: c
+├── reduces from: none
+├── lefthand of: d e, d f
+├── righthand of: none
+├── precedence: default
+├── associativity: right
+└── normal form: c"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use shell::Failure;
+
+/// Describes the result of running `input`, which starts on line `line`, as a JSON object on a single line. `kind` is `"output"` if the command returned some syntax, `"empty"` if it returned nothing, as commands that only change the context do, `"meta"` if it was a meta-command or `"error"` if it failed. `error` is the name of the `ZiaError` variant if a Zia command failed and `null` otherwise.
+pub fn result_object(
+    input: &str,
+    line: usize,
+    result: &Result<String, Failure>,
+) -> String {
+    let (kind, output, error) = match result {
+        Ok(output) if input.trim_start().starts_with(':') => {
+            ("meta", output.as_str(), None)
+        },
+        Ok(output) if output.is_empty() => ("empty", "", None),
+        Ok(output) => ("output", output.as_str(), None),
+        Err(failure) => (
+            "error",
+            failure.message.as_str(),
+            failure.error.as_ref().map(|e| e.variant_name()),
+        ),
+    };
+    format!(
+        "{{\"input\":{},\"line\":{},\"kind\":\"{}\",\"output\":{},\"error\":{}}}",
+        string(input),
+        line,
+        kind,
+        string(output),
+        error.map_or("null".to_string(), string)
+    )
+}
+
+/// Writes `s` as a JSON string.
+fn string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            '\r' => json += "\\r",
+            '\t' => json += "\\t",
+            c if c.is_control() => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -15,19 +15,216 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 //! # Interactive Shell for the Zia Programming Language.
 
+extern crate dirs;
 extern crate linefeed;
 extern crate zia;
 
-use linefeed::{Interface, ReadResult};
-use zia::Context;
+mod completer;
+mod highlight;
+mod inspect;
+mod json;
+mod parens;
+mod script;
+mod session;
+mod shell;
+mod transcript;
+
+use completer::{LabelCompleter, WORD_BREAK_CHARS};
+use highlight::{rows, Highlighter};
+use linefeed::{DefaultTerminal, Interface, ReadResult, Terminal};
+use parens::continue_command;
+use script::Format;
+use session::{data_dir, Session};
+use shell::Shell;
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, IsTerminal},
+    process,
+    sync::Arc,
+};
+use zia::{syntax::is_unbalanced, Context};
+
+const USAGE: &str = "Usage: izia [--session NAME] [--json] [SCRIPT]
+       izia --test TRANSCRIPT...
+
+Runs each line of SCRIPT as a command, or of stdin if SCRIPT is - or stdin is
+not a terminal, and exits with status 1 if any command fails. Otherwise starts
+an interactive shell.
+
+With --json, stdin is read if no SCRIPT is given and the result of each command
+is written as a JSON object on its own line.
+
+With --test, the commands of each TRANSCRIPT are run in a new context and their
+output is compared with the output written after them. Each mismatch is shown
+as a diff and izia exits with status 1 if there are any.";
 
 fn main() {
+    if env::args().nth(1).as_deref() == Some("--test") {
+        let transcripts: Vec<String> = env::args().skip(2).collect();
+        if transcripts.is_empty() {
+            usage();
+        }
+        if !test_transcripts(&transcripts) {
+            process::exit(1);
+        }
+        return;
+    }
+    let mut session = None;
+    let mut script = None;
+    let mut format = Format::Text;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => format = Format::Json,
+            "--session" if session.is_none() => match args.next() {
+                Some(name) => session = Some(name),
+                None => usage(),
+            },
+            _ if script.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                script = Some(arg)
+            },
+            _ => usage(),
+        }
+    }
+    let session = session.map(|name| {
+        Session::new(&name).unwrap_or_else(|e| {
+            eprintln!("Cannot use session {}: {}", name, e);
+            process::exit(1);
+        })
+    });
+    let cont = match session {
+        Some(ref session) => session.read().unwrap_or_else(|e| {
+            eprintln!("Cannot open session: {}", e);
+            process::exit(1);
+        }),
+        None => Context::new_without_logging(),
+    };
+    let mut shell = Shell::new(cont, session);
+    let result = match script {
+        Some(ref file) if file != "-" => File::open(file).and_then(|f| {
+            script::run(&mut shell, BufReader::new(f), file, format)
+        }),
+        Some(_) => run_stdin(&mut shell, format),
+        None if matches!(format, Format::Json)
+            || !io::stdin().is_terminal() =>
+        {
+            run_stdin(&mut shell, format)
+        },
+        None => {
+            interact(&mut shell);
+            Ok(true)
+        },
+    };
+    if let Err(e) = shell.save_session() {
+        eprintln!("Cannot save session: {}", e);
+    }
+    match result {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("Cannot read script: {}", e);
+            process::exit(1);
+        },
+    }
+}
+
+/// Runs each transcript, reporting mismatches to stderr. Returns false if a command did not
+/// give the expected output or a transcript could not be read.
+fn test_transcripts(transcripts: &[String]) -> bool {
+    let mut passed = 0;
+    let mut failed = 0;
+    for name in transcripts {
+        match fs::read_to_string(name) {
+            Ok(text) => {
+                let outcome = transcript::run(&text, name);
+                for failure in &outcome.failures {
+                    eprintln!("{}", failure);
+                }
+                passed += outcome.passed;
+                failed += outcome.failures.len();
+            },
+            Err(e) => {
+                eprintln!("Cannot read {}: {}", name, e);
+                failed += 1;
+            },
+        }
+    }
+    println!("{} passed, {} failed", passed, failed);
+    failed == 0
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn run_stdin(shell: &mut Shell, format: Format) -> io::Result<bool> {
+    let stdin = io::stdin();
+    let lock = stdin.lock();
+    script::run(shell, lock, "<stdin>", format)
+}
+
+fn interact(shell: &mut Shell) {
+    let history = data_dir().map(|dir| dir.join("history"));
     let reader = Interface::new("IZia").unwrap();
     println!("IZia Copyright (C) 2018 to 2019 Charles Johnson.\nThis program comes with ABSOLUTELY NO WARRANTY.\nThis is free software, and you are welcome to redistribute it under certain\nconditions; visit https://www.gnu.org/licenses/gpl-3.0.en.html for more details.");
     reader.set_prompt(">>> ").unwrap();
-    let mut cont = Context::new();
-    while let ReadResult::Input(input) = reader.read_line().unwrap() {
-        println!("{}", cont.execute(&input));
+    reader.set_word_break_chars(WORD_BREAK_CHARS);
+    if let Ok(ref history) = history {
+        // There is no history before the first launch
+        let _ = reader.load_history(history);
+    }
+    let completer = Arc::new(LabelCompleter::default());
+    completer.update(shell.context());
+    reader.set_completer(completer.clone());
+    let colour = io::stdout().is_terminal();
+    let terminal = DefaultTerminal::new().ok();
+    let mut highlighter = Highlighter::new(shell.context());
+    let mut depth = 0;
+    let mut input = String::new();
+    let mut prompt = ">>> ";
+    while let ReadResult::Input(line) = reader.read_line().unwrap() {
+        let meta_command =
+            input.is_empty() && line.trim_start().starts_with(':');
+        if colour && !meta_command {
+            let columns = terminal
+                .as_ref()
+                .and_then(|terminal| terminal.lock_write().size().ok())
+                .map_or(80, |size| size.columns);
+            // Replaces the rows that the line just entered wrapped onto with its highlighted syntax
+            let width = prompt.chars().count() + line.chars().count();
+            println!(
+                "{}\r{}{}",
+                "\x1b[1A\x1b[2K".repeat(rows(width, columns)),
+                prompt,
+                highlighter.highlight(&line, &mut depth)
+            );
+        }
+        continue_command(&mut input, &line);
+        if is_unbalanced(&input) {
+            prompt = "... ";
+            reader.set_prompt(prompt).unwrap();
+            continue;
+        }
+        depth = 0;
+        prompt = ">>> ";
+        reader.set_prompt(prompt).unwrap();
+        match shell.run(&input) {
+            Ok(output) => println!("{}", output),
+            Err(failure) => println!("{}", failure.message),
+        }
+        completer.update(shell.context());
+        highlighter = Highlighter::new(shell.context());
+        if !input.trim().is_empty() {
+            reader.add_history_unique(input.clone());
+        }
+        input.clear();
+    }
+    if let Ok(ref history) = history {
+        if let Err(e) = reader.save_history(history) {
+            eprintln!("Cannot save history: {}", e);
+        }
     }
     println!("Exiting");
 }
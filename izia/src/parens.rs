@@ -0,0 +1,37 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Adds `line` to a command that continues over several lines.
+pub fn continue_command(command: &mut String, line: &str) {
+    if !command.is_empty() {
+        command.push(' ');
+    }
+    command.push_str(line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::continue_command;
+
+    #[test]
+    fn lines_are_joined_with_spaces() {
+        let mut command = String::new();
+        continue_command(&mut command, "let (a");
+        assert_eq!(command, "let (a");
+        continue_command(&mut command, "  b) -> c");
+        assert_eq!(command, "let (a   b) -> c");
+    }
+}
@@ -0,0 +1,83 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use json::result_object;
+use parens::continue_command;
+use shell::Shell;
+use std::io::{self, BufRead};
+use zia::syntax::is_unbalanced;
+
+/// How the results of the commands of a script are written.
+#[derive(Clone, Copy)]
+pub enum Format {
+    /// The output of each command is written to stdout and the message of each failed command is written to stderr, prefixed by the name of the script and the line number where the command starts.
+    Text,
+    /// A JSON object describing the result of each command is written to stdout.
+    Json,
+}
+
+/// Runs each line of `script` as a command, skipping blank lines. A command whose parentheses are not balanced continues on the following lines. Returns `Ok(false)` if any command failed.
+pub fn run<R: BufRead>(
+    shell: &mut Shell,
+    script: R,
+    name: &str,
+    format: Format,
+) -> io::Result<bool> {
+    let mut succeeded = true;
+    let mut command = String::new();
+    let mut start = 0;
+    for (index, line) in script.lines().enumerate() {
+        let line = line?;
+        if command.is_empty() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            start = index + 1;
+        }
+        continue_command(&mut command, &line);
+        if is_unbalanced(&command) {
+            continue;
+        }
+        succeeded &= run_command(shell, &command, name, start, format);
+        command.clear();
+    }
+    if !command.is_empty() {
+        // Zia reports the missing parentheses
+        succeeded &= run_command(shell, &command, name, start, format);
+    }
+    Ok(succeeded)
+}
+
+fn run_command(
+    shell: &mut Shell,
+    command: &str,
+    name: &str,
+    line: usize,
+    format: Format,
+) -> bool {
+    let result = shell.run(command);
+    match (format, &result) {
+        (Format::Json, _) => {
+            println!("{}", result_object(command, line, &result))
+        },
+        (Format::Text, Ok(output)) if output.is_empty() => (),
+        (Format::Text, Ok(output)) => println!("{}", output),
+        (Format::Text, Err(failure)) => {
+            eprintln!("{}:{}: {}", name, line, failure.message)
+        },
+    }
+    result.is_ok()
+}
@@ -0,0 +1,104 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use dirs;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use zia::Context;
+
+/// Returns the directory where izia keeps its files, creating it if needed.
+pub fn data_dir() -> io::Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no data directory")
+        })?
+        .join("izia");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// The files that a named session is saved in. The snapshot holds the context as it was when the session was opened and the journal holds each change made since.
+pub struct Session {
+    snapshot: PathBuf,
+    journal: PathBuf,
+}
+
+impl Session {
+    /// Returns the session called `name` without reading or writing its files.
+    pub fn new(name: &str) -> io::Result<Self> {
+        Self::in_dir(&data_dir()?.join("sessions"), name)
+    }
+
+    fn in_dir(dir: &Path, name: &str) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            snapshot: dir.join(format!("{}.snapshot", name)),
+            journal: dir.join(format!("{}.journal", name)),
+        })
+    }
+
+    /// Reads the context saved in the session, or a new context if nothing has been saved yet.
+    pub fn read(&self) -> io::Result<Context> {
+        if self.snapshot.exists() {
+            Context::recover(&self.snapshot, &self.journal)
+                .map(Context::without_logging)
+        } else {
+            Ok(Context::new_without_logging())
+        }
+    }
+
+    /// Makes `cont` the context of the session, saving it and each change made to it from now on.
+    pub fn attach(&self, cont: &mut Context) -> io::Result<()> {
+        self.save(cont)?;
+        cont.journal(&self.journal)
+    }
+
+    /// Saves the whole context as the snapshot of the session and empties the journal, so the journal does not keep growing from one session to the next.
+    pub fn save(&self, cont: &Context) -> io::Result<()> {
+        cont.save_snapshot(&self.snapshot)?;
+        if self.journal.exists() {
+            fs::remove_file(&self.journal)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use std::{env::temp_dir, fs::remove_dir_all, process};
+
+    #[test]
+    fn sessions_are_reopened_with_the_same_rules() {
+        let dir = temp_dir().join(format!("izia-{}-sessions", process::id()));
+        let session = Session::in_dir(&dir, "test").unwrap();
+        let mut cont = session.read().unwrap();
+        assert_eq!(cont.execute("let a -> b"), "");
+        session.attach(&mut cont).unwrap();
+        // Journalled after the snapshot was saved
+        assert_eq!(cont.execute("let c := a d"), "");
+        let reopened = Session::in_dir(&dir, "test").unwrap().read().unwrap();
+        assert!(cont.diff_semantic(&reopened).unwrap().is_empty());
+        assert_eq!(cont.execute("let e -> f"), "");
+        session.save(&cont).unwrap();
+        let mut reopened =
+            Session::in_dir(&dir, "test").unwrap().read().unwrap();
+        assert!(cont.diff_semantic(&reopened).unwrap().is_empty());
+        assert_eq!(reopened.execute("c"), "b d");
+        remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,390 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use inspect::render;
+use session::Session;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use zia::{Context, ZiaError};
+
+/// The most steps shown when tracing the reduction of a command.
+const MAX_TRACE_STEPS: usize = 100;
+
+const HELP: &str = "Commands starting with ':' are handled by izia rather than Zia:
+:save FILE   save the context to FILE
+:load FILE   replace the context with the one saved in FILE
+:inspect EXPRESSION
+             show everything about the concept of EXPRESSION
+:undo        undo the last command that changed the context
+:trace       show each reduction step of the commands that follow, or stop showing them
+:reset       replace the context with a new one
+:help        show this message";
+
+/// Commands handled by the shell itself rather than the context. They start with `:` as no Zia expression can.
+#[derive(Debug, PartialEq)]
+pub enum MetaCommand {
+    Save(String),
+    Load(String),
+    Inspect(String),
+    Undo,
+    Trace,
+    Reset,
+    Help,
+}
+
+impl MetaCommand {
+    /// Reads a meta-command from `input`. Returns `None` if `input` is not a meta-command or `Some(Err)` with a message if it is malformed.
+    pub fn parse(input: &str) -> Option<Result<Self, String>> {
+        let input = input.trim();
+        if !input.starts_with(':') {
+            return None;
+        }
+        let mut words = input[1..].splitn(2, char::is_whitespace);
+        let name = words.next().unwrap_or("");
+        let argument = words.next().map(str::trim).filter(|a| !a.is_empty());
+        Some(match (name, argument) {
+            ("save", Some(file)) => Ok(MetaCommand::Save(file.to_string())),
+            ("load", Some(file)) => Ok(MetaCommand::Load(file.to_string())),
+            ("inspect", Some(expression)) => {
+                Ok(MetaCommand::Inspect(expression.to_string()))
+            },
+            ("save", None) | ("load", None) => {
+                Err(format!(":{} needs the name of a file", name))
+            },
+            ("inspect", None) => {
+                Err(":inspect needs an expression".to_string())
+            },
+            ("undo", None) => Ok(MetaCommand::Undo),
+            ("trace", None) => Ok(MetaCommand::Trace),
+            ("reset", None) => Ok(MetaCommand::Reset),
+            ("help", None) => Ok(MetaCommand::Help),
+            ("undo", _) | ("trace", _) | ("reset", _) | ("help", _) => {
+                Err(format!(":{} takes no arguments", name))
+            },
+            _ => Err(format!("Unknown command :{}. Try :help", name)),
+        })
+    }
+}
+
+/// A command that failed.
+pub struct Failure {
+    /// What to show for the failure, including any reduction steps traced before it.
+    pub message: String,
+    /// The error of the context, or `None` if a meta-command failed.
+    pub error: Option<ZiaError>,
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            error: None,
+        }
+    }
+}
+
+/// Executes commands on a context, keeping what is needed to undo them and to save the context in a session.
+pub struct Shell {
+    cont: Context,
+    session: Option<Session>,
+    /// Set by the context whenever a command changes it.
+    changed: Arc<AtomicBool>,
+    /// The number of commands that can be undone. The context has a checkpoint named by `undo_checkpoint` for each of them.
+    undo_depth: usize,
+    tracing: bool,
+}
+
+impl Shell {
+    pub fn new(cont: Context, session: Option<Session>) -> Self {
+        let mut shell = Self {
+            cont,
+            session,
+            changed: Arc::new(AtomicBool::new(false)),
+            undo_depth: 0,
+            tracing: false,
+        };
+        shell.watch_context();
+        shell
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.cont
+    }
+
+    /// Handles `input` as a meta-command if it starts with `:` or executes it on the context otherwise. Returns the output or why the command failed.
+    pub fn run(&mut self, input: &str) -> Result<String, Failure> {
+        match MetaCommand::parse(input) {
+            Some(Ok(command)) => {
+                self.run_meta_command(command).map_err(Failure::from)
+            },
+            Some(Err(message)) => Err(Failure::from(message)),
+            None => self.execute(input),
+        }
+    }
+
+    /// Saves the context in the session, if there is one.
+    pub fn save_session(&self) -> io::Result<()> {
+        match self.session {
+            Some(ref session) => session.save(&self.cont),
+            None => Ok(()),
+        }
+    }
+
+    fn execute(&mut self, command: &str) -> Result<String, Failure> {
+        let mut output = String::new();
+        if self.tracing {
+            if let Err(e) = self.trace(command, &mut output) {
+                return Err(Failure {
+                    message: output + &e.to_string(),
+                    error: Some(e),
+                });
+            }
+        }
+        let checkpoint = undo_checkpoint(self.undo_depth);
+        self.cont.checkpoint(&checkpoint);
+        self.changed.store(false, Ordering::SeqCst);
+        let result = self.cont.try_execute(command);
+        if self.changed.load(Ordering::SeqCst) {
+            self.undo_depth += 1;
+        } else {
+            self.cont.remove_checkpoint(&checkpoint);
+        }
+        match result {
+            Ok(result) => Ok(output + &result),
+            Err(e) => Err(Failure {
+                message: output + &e.to_string(),
+                error: Some(e),
+            }),
+        }
+    }
+
+    /// Writes the syntax of each step taken to reduce `command` to `output`, leaving out the last one as it is the output of the command. Tracing stops at the first step that fails, returning its error. The steps are taken on a fork of the context so that they are not journalled, observed or undoable.
+    fn trace(
+        &self,
+        command: &str,
+        output: &mut String,
+    ) -> Result<(), ZiaError> {
+        let mut cont = self.cont.fork();
+        let mut steps: Vec<String> = Vec::new();
+        let mut expression = command.to_string();
+        let mut result = Ok(());
+        while steps.len() < MAX_TRACE_STEPS {
+            match cont.try_execute(&format!("step ({})", expression)) {
+                Ok(next) if next == expression || steps.contains(&next) => {
+                    break
+                },
+                Ok(next) => {
+                    steps.push(next.clone());
+                    expression = next;
+                },
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+        }
+        if result.is_ok() {
+            steps.pop();
+        }
+        for step in steps {
+            *output += &format!("~> {}\n", step);
+        }
+        result
+    }
+
+    fn run_meta_command(
+        &mut self,
+        command: MetaCommand,
+    ) -> Result<String, String> {
+        match command {
+            MetaCommand::Save(file) => self
+                .cont
+                .save_snapshot(&file)
+                .map(|()| format!("Saved the context to {}", file))
+                .map_err(|e| format!("Cannot save to {}: {}", file, e)),
+            MetaCommand::Load(file) => Context::load_snapshot(&file)
+                .map(|cont| {
+                    self.replace_context(cont.without_logging());
+                    format!("Loaded the context from {}", file)
+                })
+                .map_err(|e| format!("Cannot load {}: {}", file, e)),
+            MetaCommand::Inspect(expression) => {
+                match self.cont.inspect(&expression) {
+                    Ok(Some(details)) => Ok(render(&expression, &details)),
+                    Ok(None) => {
+                        Err(format!("{} has no concept yet", expression))
+                    },
+                    Err(e) => Err(e.to_string()),
+                }
+            },
+            MetaCommand::Undo => {
+                if self.undo_depth == 0 {
+                    return Err("Nothing to undo".to_string());
+                }
+                self.undo_depth -= 1;
+                let checkpoint = undo_checkpoint(self.undo_depth);
+                self.cont.restore(&checkpoint);
+                self.cont.remove_checkpoint(&checkpoint);
+                Ok("Undone".to_string())
+            },
+            MetaCommand::Trace => {
+                self.tracing = !self.tracing;
+                Ok(if self.tracing {
+                    "Tracing reductions".to_string()
+                } else {
+                    "Stopped tracing reductions".to_string()
+                })
+            },
+            MetaCommand::Reset => {
+                self.replace_context(Context::new_without_logging());
+                Ok("Reset the context".to_string())
+            },
+            MetaCommand::Help => Ok(HELP.to_string()),
+        }
+    }
+
+    /// Uses `cont` from now on. The commands executed on the previous context can no longer be undone.
+    fn replace_context(&mut self, cont: Context) {
+        self.cont = cont;
+        self.undo_depth = 0;
+        self.watch_context();
+    }
+
+    /// Finds out when the context changes and saves it in the session, if there is one.
+    fn watch_context(&mut self) {
+        let changed = self.changed.clone();
+        self.cont.subscribe(move |_| changed.store(true, Ordering::SeqCst));
+        if let Some(ref session) = self.session {
+            if let Err(e) = session.attach(&mut self.cont) {
+                eprintln!("Cannot save session: {}", e);
+            }
+        }
+    }
+}
+
+fn undo_checkpoint(depth: usize) -> String {
+    format!("undo {}", depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MetaCommand, Shell};
+    use zia::{Context, ZiaError};
+
+    fn shell() -> Shell {
+        Shell::new(Context::new_without_logging(), None)
+    }
+
+    /// The output of `input`, or the message of its failure.
+    fn run(shell: &mut Shell, input: &str) -> String {
+        shell.run(input).unwrap_or_else(|failure| failure.message)
+    }
+
+    #[test]
+    fn meta_commands_are_parsed() {
+        assert_eq!(MetaCommand::parse("let a -> b"), None);
+        assert_eq!(
+            MetaCommand::parse(" :save  my file "),
+            Some(Ok(MetaCommand::Save("my file".to_string())))
+        );
+        assert_eq!(
+            MetaCommand::parse(":inspect a b"),
+            Some(Ok(MetaCommand::Inspect("a b".to_string())))
+        );
+        assert_eq!(MetaCommand::parse(":undo"), Some(Ok(MetaCommand::Undo)));
+        assert_eq!(MetaCommand::parse(":trace"), Some(Ok(MetaCommand::Trace)));
+        assert_eq!(MetaCommand::parse(":reset"), Some(Ok(MetaCommand::Reset)));
+    }
+
+    #[test]
+    fn malformed_meta_commands_are_explained() {
+        assert_eq!(
+            MetaCommand::parse(":load"),
+            Some(Err(":load needs the name of a file".to_string()))
+        );
+        assert_eq!(
+            MetaCommand::parse(":inspect "),
+            Some(Err(":inspect needs an expression".to_string()))
+        );
+        assert_eq!(
+            MetaCommand::parse(":undo 2"),
+            Some(Err(":undo takes no arguments".to_string()))
+        );
+        assert_eq!(
+            MetaCommand::parse(":redo"),
+            Some(Err("Unknown command :redo. Try :help".to_string()))
+        );
+    }
+
+    #[test]
+    fn tracing_shows_each_step_before_the_output() {
+        let mut shell = shell();
+        assert_eq!(run(&mut shell, "let (a b) -> c"), "");
+        assert_eq!(run(&mut shell, "let c -> d"), "");
+        assert_eq!(run(&mut shell, ":trace"), "Tracing reductions");
+        assert_eq!(run(&mut shell, "a b"), "~> c\nd");
+        assert_eq!(run(&mut shell, "e"), "e");
+        assert_eq!(run(&mut shell, ":trace"), "Stopped tracing reductions");
+        assert_eq!(run(&mut shell, "a b"), "d");
+    }
+
+    #[test]
+    fn tracing_stops_at_the_first_error() {
+        let mut shell = shell();
+        assert_eq!(run(&mut shell, ":trace"), "Tracing reductions");
+        let failure = shell.run(")").unwrap_err();
+        assert_eq!(failure.message, "Missing (");
+        assert_eq!(
+            failure.error,
+            Some(ZiaError::MissingSymbol {
+                symbol: "("
+            })
+        );
+    }
+
+    #[test]
+    fn undo_reverts_each_change_in_turn() {
+        let mut shell = shell();
+        assert_eq!(run(&mut shell, ":undo"), "Nothing to undo");
+        assert_eq!(run(&mut shell, "let a -> b"), "");
+        assert_eq!(run(&mut shell, "let a -> c"), "");
+        // Neither changes the context so neither can be undone
+        assert_eq!(run(&mut shell, "a"), "c");
+        assert_eq!(
+            run(&mut shell, "let a -> c"),
+            "That reduction rule already exists."
+        );
+        assert_eq!(run(&mut shell, ":undo"), "Undone");
+        assert_eq!(run(&mut shell, "a"), "b");
+        assert_eq!(run(&mut shell, ":undo"), "Undone");
+        assert_eq!(run(&mut shell, "a"), "a");
+        assert_eq!(run(&mut shell, ":undo"), "Nothing to undo");
+    }
+
+    #[test]
+    fn reset_forgets_the_context_and_what_can_be_undone() {
+        let mut shell = shell();
+        assert_eq!(run(&mut shell, "let a -> b"), "");
+        assert_eq!(run(&mut shell, ":reset"), "Reset the context");
+        assert_eq!(run(&mut shell, "a"), "a");
+        assert_eq!(run(&mut shell, ":undo"), "Nothing to undo");
+    }
+}
@@ -0,0 +1,229 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// A transcript looks like an izia session: each command follows a `>>> `
+// prompt, continues after `... ` prompts, and is followed by the output it is
+// expected to give, which is nothing if the next line is a prompt. Blank lines
+// at the end of an expected output are ignored, as are the lines before the
+// first prompt, which can describe what the transcript tests.
+
+use shell::Shell;
+use zia::Context;
+
+const PROMPT: &str = ">>> ";
+const CONTINUATION: &str = "... ";
+
+/// A command of a transcript and the output it is expected to give.
+struct Exchange {
+    /// The line of the transcript where the command starts.
+    line: usize,
+    command: String,
+    expected: Vec<String>,
+}
+
+/// How many commands of a transcript gave the expected output and a report of each that did not.
+pub struct Outcome {
+    pub passed: usize,
+    pub failures: Vec<String>,
+}
+
+/// Runs the commands of `transcript` in a new context, comparing their output with what was expected. `name` is used to say where each mismatch is.
+pub fn run(transcript: &str, name: &str) -> Outcome {
+    let mut shell = Shell::new(Context::new_without_logging(), None);
+    let mut outcome = Outcome {
+        passed: 0,
+        failures: Vec::new(),
+    };
+    for exchange in parse(transcript) {
+        let output = match shell.run(&exchange.command) {
+            Ok(output) => output,
+            Err(failure) => failure.message,
+        };
+        let actual: Vec<&str> = if output.is_empty() {
+            Vec::new()
+        } else {
+            output.lines().collect()
+        };
+        if actual == exchange.expected {
+            outcome.passed += 1;
+        } else {
+            let mut report = format!(
+                "{}:{}: {}{}\n",
+                name, exchange.line, PROMPT, exchange.command
+            );
+            for line in diff(&exchange.expected, &actual) {
+                report += &line;
+                report.push('\n');
+            }
+            outcome.failures.push(report);
+        }
+    }
+    outcome
+}
+
+fn parse(transcript: &str) -> Vec<Exchange> {
+    let mut exchanges: Vec<Exchange> = Vec::new();
+    let mut continuing = false;
+    for (index, line) in transcript.lines().enumerate() {
+        if let Some(command) = line.strip_prefix(PROMPT) {
+            exchanges.push(Exchange {
+                line: index + 1,
+                command: command.to_string(),
+                expected: Vec::new(),
+            });
+            continuing = true;
+            continue;
+        }
+        if let Some(exchange) = exchanges.last_mut() {
+            match line.strip_prefix(CONTINUATION) {
+                Some(rest) if continuing => {
+                    exchange.command.push(' ');
+                    exchange.command.push_str(rest);
+                },
+                _ => {
+                    exchange.expected.push(line.to_string());
+                    continuing = false;
+                },
+            }
+        }
+    }
+    for exchange in &mut exchanges {
+        while exchange.expected.last().is_some_and(|l| l.trim().is_empty()) {
+            exchange.expected.pop();
+        }
+    }
+    exchanges
+}
+
+/// Lines of the expected output missing from the actual output are marked with `-` and lines
+/// of the actual output that were not expected are marked with `+`.
+fn diff(expected: &[String], actual: &[&str]) -> Vec<String> {
+    // The length of the longest common subsequence of the remaining lines
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(format!("  {}", actual[j]));
+            i += 1;
+            j += 1;
+        } else if j < actual.len()
+            && (i == expected.len() || common[i][j + 1] >= common[i + 1][j])
+        {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        } else {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, parse};
+
+    fn parsed(transcript: &str) -> Vec<(usize, String, Vec<String>)> {
+        parse(transcript)
+            .into_iter()
+            .map(|e| (e.line, e.command, e.expected))
+            .collect()
+    }
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn commands_and_expected_output() {
+        let transcript = "Tests reductions.\n\n>>> let a -> b\n>>> a\nb\n>>> let (c\n...   d) -> e\n>>> :help\nSome\n\nhelp\n";
+        assert_eq!(
+            parsed(transcript),
+            [
+                (3, "let a -> b".to_string(), Vec::new()),
+                (4, "a".to_string(), strings(&["b"])),
+                (6, "let (c   d) -> e".to_string(), Vec::new()),
+                (8, ":help".to_string(), strings(&["Some", "", "help"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_blank_lines_are_ignored() {
+        assert_eq!(
+            parsed(">>> a\nb\n\n  \n>>> c\n\n"),
+            [
+                (1, "a".to_string(), strings(&["b"])),
+                (5, "c".to_string(), Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn continuations_only_follow_commands() {
+        assert_eq!(
+            parsed(">>> a\nb\n... c"),
+            [(1, "a".to_string(), strings(&["b", "... c"]))]
+        );
+    }
+
+    #[test]
+    fn transcripts_without_prompts_have_no_commands() {
+        assert!(parsed("").is_empty());
+        assert!(parsed("... a\nb\n>>>c").is_empty());
+    }
+
+    #[test]
+    fn matching_lines_are_unmarked() {
+        assert_eq!(diff(&strings(&["a", "b"]), &["a", "b"]), ["  a", "  b"]);
+    }
+
+    #[test]
+    fn inserted_lines_are_marked_with_plus() {
+        assert_eq!(
+            diff(&strings(&["a", "c"]), &["a", "b", "c", "d"]),
+            ["  a", "+ b", "  c", "+ d"]
+        );
+        assert_eq!(diff(&[], &["a"]), ["+ a"]);
+    }
+
+    #[test]
+    fn deleted_lines_are_marked_with_minus() {
+        assert_eq!(
+            diff(&strings(&["a", "b", "c", "d"]), &["b", "d"]),
+            ["- a", "  b", "- c", "  d"]
+        );
+        assert_eq!(diff(&strings(&["a"]), &[]), ["- a"]);
+    }
+
+    #[test]
+    fn changed_lines_are_inserted_before_they_are_deleted() {
+        assert_eq!(
+            diff(&strings(&["a", "b", "c"]), &["a", "x", "y", "c"]),
+            ["  a", "+ x", "+ y", "- b", "  c"]
+        );
+    }
+}
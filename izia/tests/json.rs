@@ -0,0 +1,50 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+extern crate serde_json;
+
+use serde_json::Value;
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[test]
+fn each_line_of_json_output_is_an_object() {
+    let mut izia = Command::new(env!("CARGO_BIN_EXE_izia"))
+        .arg("--json")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    izia.stdin
+        .take()
+        .unwrap()
+        .write_all(b"let a -> b\na\n\nlet (c d\n) -> e\n:inspect a\n:reset\nlet a -> a\n")
+        .unwrap();
+    let output = izia.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let kinds: Vec<String> = stdout
+        .lines()
+        .map(|line| {
+            let object: Value = serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("{:?} is not JSON: {}", line, e));
+            object["kind"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(kinds, ["empty", "output", "empty", "meta", "meta", "error"]);
+}
@@ -0,0 +1,102 @@
+// IZia: Interactive Shell for the Zia Programming Language.
+// Copyright (C) 2018 to 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    env::temp_dir,
+    fs::{remove_file, write},
+    io::Write,
+    process::{self, Command, Output, Stdio},
+};
+
+/// Runs izia with `args`, writing `input` to its stdin.
+fn izia(args: &[&str], input: &str) -> Output {
+    let mut izia = Command::new(env!("CARGO_BIN_EXE_izia"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    izia.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    izia.wait_with_output().unwrap()
+}
+
+/// The lines written to stderr that report a failed command of `name`.
+fn reports(output: &Output, name: &str) -> Vec<String> {
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter(|line| line.starts_with(&format!("{}:", name)))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn successful_scripts_exit_with_status_0() {
+    let output = izia(&[], "let a -> b\na\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout.clone()).unwrap(), "b\n");
+    assert!(reports(&output, "<stdin>").is_empty());
+}
+
+#[test]
+fn failed_commands_are_reported_on_stderr() {
+    let output = izia(&[], "let a -> b\nlet a -> b\na\n");
+    assert_eq!(output.status.code(), Some(1));
+    // The commands after a failed one still run
+    assert_eq!(String::from_utf8(output.stdout.clone()).unwrap(), "b\n");
+    assert_eq!(
+        reports(&output, "<stdin>"),
+        ["<stdin>:2: That reduction rule already exists."]
+    );
+}
+
+#[test]
+fn failed_commands_are_reported_with_the_name_of_the_script() {
+    let script = temp_dir().join(format!("izia-{}-failing.zia", process::id()));
+    let name = script.to_str().unwrap();
+    write(&script, "let a -> b\n\nlet b -> a\n").unwrap();
+    let output = izia(&[name], "");
+    remove_file(&script).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        reports(&output, name),
+        [format!(
+            "{}:3: Cannot allow a chain of reduction rules to loop.",
+            name
+        )]
+    );
+}
+
+#[test]
+fn unbalanced_commands_continue_on_the_next_lines() {
+    let output = izia(&[], "let (a\n  b) -> c\n(a\n\nb)\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "c\n");
+}
+
+#[test]
+fn failed_commands_are_reported_at_their_first_line() {
+    let output = izia(&[], "let (b a) -> c\n\nlet c -> (b\n  a)\n(c\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        reports(&output, "<stdin>"),
+        [
+            "<stdin>:3: Cannot allow a chain of reduction rules to loop.",
+            "<stdin>:5: Missing )"
+        ]
+    );
+}
@@ -65,6 +65,14 @@ lazy_static! {
         cs.insert("prec".to_string());
         cs.insert("default".to_string());
         cs.insert(">".to_string());
+        cs.insert("step".to_string());
+        cs.insert("steps".to_string());
+        cs.insert("expand".to_string());
+        cs.insert("definition_of".to_string());
+        cs.insert("reduction_of".to_string());
+        cs.insert("reduces_from".to_string());
+        cs.insert("forget".to_string());
+        cs.insert("assuming".to_string());
         cs
     };
 }
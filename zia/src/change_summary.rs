@@ -0,0 +1,81 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// What changed when a context committed the changes of a command, given in terms of syntax rather than how concepts are stored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChangeSummary {
+    labels_added: Vec<String>,
+    labels_removed: Vec<String>,
+    reductions_changed: Vec<String>,
+    definitions_changed: Vec<String>,
+}
+
+impl ChangeSummary {
+    pub(crate) fn new(
+        labels_added: Vec<String>,
+        labels_removed: Vec<String>,
+        reductions: (&BTreeMap<String, String>, &BTreeMap<String, String>),
+        definitions: (&BTreeMap<String, String>, &BTreeMap<String, String>),
+    ) -> Self {
+        Self {
+            labels_added,
+            labels_removed,
+            reductions_changed: changed_keys(reductions.0, reductions.1),
+            definitions_changed: changed_keys(definitions.0, definitions.1),
+        }
+    }
+
+    /// The labels that were given to concepts.
+    pub fn labels_added(&self) -> &[String] {
+        &self.labels_added
+    }
+
+    /// The labels that no longer label any concept.
+    pub fn labels_removed(&self) -> &[String] {
+        &self.labels_removed
+    }
+
+    /// The syntax of the concepts whose reduction rules were added, changed or removed.
+    pub fn reductions_changed(&self) -> &[String] {
+        &self.reductions_changed
+    }
+
+    /// The labels of the concepts whose definitions were added, changed or removed.
+    pub fn definitions_changed(&self) -> &[String] {
+        &self.definitions_changed
+    }
+
+    /// Returns true if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.labels_added.is_empty()
+            && self.labels_removed.is_empty()
+            && self.reductions_changed.is_empty()
+            && self.definitions_changed.is_empty()
+    }
+}
+
+fn changed_keys(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,47 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+/// Whether a concept is built-in, can be composed of and reduce to other concepts, or holds the string of a label.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConceptKind {
+    Concrete,
+    Abstract,
+    String(String),
+}
+
+/// Everything about a concept, with related concepts given by their syntax.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConceptDetails {
+    /// The index the concept is stored at.
+    pub id: usize,
+    pub kind: ConceptKind,
+    /// The syntax of the concepts it is composed of.
+    pub definition: Option<String>,
+    /// The syntax of the concept it directly reduces to.
+    pub reduction: Option<String>,
+    /// The syntax of the concepts that directly reduce to it.
+    pub reduces_from: Vec<String>,
+    /// The syntax of the concepts it is the lefthand part of.
+    pub lefthand_of: Vec<String>,
+    /// The syntax of the concepts it is the righthand part of.
+    pub righthand_of: Vec<String>,
+    /// What `prec` reduces to for the concept.
+    pub precedence: String,
+    /// What `assoc` reduces to for the concept.
+    pub associativity: String,
+    /// What the concept reduces to once no more reduction rules apply.
+    pub normal_form: String,
+}
@@ -15,7 +15,8 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use delta::{Apply, Change, Delta};
-use std::fmt::Debug;
+use errors::ZiaResult;
+use std::{collections::HashMap, fmt::Debug};
 
 /// An abstract concept can reduce to other concepts and be defined as a composition of two other concepts.
 #[derive(Clone, PartialEq)]
@@ -59,9 +60,10 @@ impl Apply for AbstractPart {
 }
 
 impl Delta for AbstractDelta {
-    fn combine(&mut self, other: Self) {
-        self.definition = self.definition.clone().combine(other.definition);
-        self.reduction = self.reduction.clone().combine(other.reduction);
+    fn combine(&mut self, other: Self) -> ZiaResult<()> {
+        self.definition = self.definition.clone().combine(other.definition)?;
+        self.reduction = self.reduction.clone().combine(other.reduction)?;
+        Ok(())
     }
 }
 
@@ -93,6 +95,13 @@ impl AbstractPart {
         }
     }
 
+    /// Changes the indices of the definition and reduction according to `mapping`.
+    pub fn remap(&mut self, mapping: &HashMap<usize, usize>) {
+        self.definition =
+            self.definition.map(|(l, r)| (mapping[&l], mapping[&r]));
+        self.reduces_to = self.reduces_to.map(|r| mapping[&r]);
+    }
+
     pub fn make_reduce_to_delta(&self, concept: usize) -> AbstractDelta {
         AbstractDelta {
             definition: Change::Same,
@@ -18,7 +18,10 @@ mod abstract_part;
 pub use self::abstract_part::{AbstractDelta, AbstractPart};
 use delta::{Apply, Change, Delta, SetChange};
 use errors::{ZiaError, ZiaResult};
-use std::{collections::HashSet, fmt::Debug};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+};
 
 /// Data type for any type of concept.
 #[derive(Clone, Default, PartialEq)]
@@ -148,6 +151,29 @@ impl Concept {
         }
     }
 
+    /// Changes the indices of related concepts according to `mapping`.
+    pub fn remap(&mut self, mapping: &HashMap<usize, usize>) {
+        self.lefthand_of =
+            self.lefthand_of.iter().map(|c| mapping[c]).collect();
+        self.righthand_of =
+            self.righthand_of.iter().map(|c| mapping[c]).collect();
+        self.reduces_from =
+            self.reduces_from.iter().map(|c| mapping[c]).collect();
+        if let SpecificPart::Abstract(ref mut ap) = self.specific_part {
+            ap.remap(mapping);
+        }
+    }
+
+    /// Whether the concept can reduce to other concepts and be composed of other concepts.
+    pub fn is_abstract(&self) -> bool {
+        matches!(self.specific_part, SpecificPart::Abstract(_))
+    }
+
+    /// Whether the concept is one of the built-in concepts that cannot be composed of or reduce to other concepts.
+    pub fn is_concrete(&self) -> bool {
+        matches!(self.specific_part, SpecificPart::Concrete)
+    }
+
     pub const fn get_lefthand_of(&self) -> &HashSet<usize> {
         &self.lefthand_of
     }
@@ -225,6 +251,85 @@ impl Concept {
             _ => Err(ZiaError::SettingDefinitionOfConcrete),
         }
     }
+
+    /// Writes the concept on a single line in the format used by journals and snapshot files.
+    pub fn to_record(&self) -> String {
+        let specific_part = match self.specific_part {
+            SpecificPart::Concrete => "concrete".to_string(),
+            SpecificPart::Abstract(ref ap) => format!(
+                "abstract {} {}",
+                ap.definition
+                    .map_or("-".to_string(), |(l, r)| format!("{},{}", l, r)),
+                ap.reduces_to.map_or("-".to_string(), |r| r.to_string())
+            ),
+            SpecificPart::String(ref s) => format!("string {}", s),
+        };
+        format!(
+            "{} {} {} {}",
+            ids_record(&self.lefthand_of),
+            ids_record(&self.righthand_of),
+            ids_record(&self.reduces_from),
+            specific_part
+        )
+    }
+
+    /// Reads a concept written by `to_record`. Returns `None` if the record is malformed.
+    pub fn from_record(record: &str) -> Option<Self> {
+        let mut fields = record.splitn(5, ' ');
+        let lefthand_of = ids_from_record(fields.next()?)?;
+        let righthand_of = ids_from_record(fields.next()?)?;
+        let reduces_from = ids_from_record(fields.next()?)?;
+        let specific_part = match (fields.next()?, fields.next()) {
+            ("concrete", None) => SpecificPart::Concrete,
+            ("abstract", Some(rest)) => {
+                let mut rest = rest.split(' ');
+                let definition = match rest.next()? {
+                    "-" => None,
+                    d => match ids_from_record(d)?.as_slice() {
+                        [l, r] => Some((*l, *r)),
+                        _ => return None,
+                    },
+                };
+                let reduces_to = match rest.next()? {
+                    "-" => None,
+                    r => Some(r.parse().ok()?),
+                };
+                if rest.next().is_some() {
+                    return None;
+                }
+                SpecificPart::Abstract(AbstractPart {
+                    definition,
+                    reduces_to,
+                })
+            },
+            ("string", Some(s)) => SpecificPart::String(s.to_string()),
+            _ => return None,
+        };
+        Some(Self {
+            lefthand_of: lefthand_of.into_iter().collect(),
+            righthand_of: righthand_of.into_iter().collect(),
+            reduces_from: reduces_from.into_iter().collect(),
+            specific_part,
+        })
+    }
+}
+
+/// Writes indices in ascending order separated by commas, or `-` if there are none.
+fn ids_record(ids: &HashSet<usize>) -> String {
+    if ids.is_empty() {
+        return "-".to_string();
+    }
+    let mut ids: Vec<&usize> = ids.iter().collect();
+    ids.sort();
+    ids.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn ids_from_record(record: &str) -> Option<Vec<usize>> {
+    if record == "-" {
+        Some(Vec::new())
+    } else {
+        record.split(',').map(|id| id.parse().ok()).collect()
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -299,12 +404,113 @@ impl std::fmt::Debug for ConceptDelta {
 }
 
 impl Delta for ConceptDelta {
-    fn combine(&mut self, other: Self) {
-        self.specific_part.combine(other.specific_part);
-        self.lefthand_of.combine(other.lefthand_of);
-        self.righthand_of.combine(other.righthand_of);
-        self.reduces_from.combine(other.reduces_from);
+    fn combine(&mut self, other: Self) -> ZiaResult<()> {
+        self.specific_part.combine(other.specific_part)?;
+        self.lefthand_of.combine(other.lefthand_of)?;
+        self.righthand_of.combine(other.righthand_of)?;
+        self.reduces_from.combine(other.reduces_from)
+    }
+}
+
+impl ConceptDelta {
+    /// Writes the changes on a single line in the format used by journals. Only what is needed to apply the changes is written.
+    pub fn to_record(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            set_change_record(&self.lefthand_of),
+            set_change_record(&self.righthand_of),
+            set_change_record(&self.reduces_from),
+            match self.specific_part.definition {
+                Change::Same => "=".to_string(),
+                Change::Different {
+                    after,
+                    ..
+                } => after
+                    .map_or("-".to_string(), |(l, r)| format!("{},{}", l, r)),
+            },
+            match self.specific_part.reduction {
+                Change::Same => "=".to_string(),
+                Change::Different {
+                    after,
+                    ..
+                } => after.map_or("-".to_string(), |r| r.to_string()),
+            }
+        )
+    }
+
+    /// Reads changes written by `to_record`. Returns `None` if the record is malformed.
+    pub fn from_record(record: &str) -> Option<Self> {
+        let mut fields = record.split(' ');
+        let lefthand_of = set_change_from_record(fields.next()?)?;
+        let righthand_of = set_change_from_record(fields.next()?)?;
+        let reduces_from = set_change_from_record(fields.next()?)?;
+        let definition = match fields.next()? {
+            "=" => Change::Same,
+            "-" => Change::Different {
+                before: None,
+                after: None,
+            },
+            d => match ids_from_record(d)?.as_slice() {
+                [l, r] => Change::Different {
+                    before: None,
+                    after: Some((*l, *r)),
+                },
+                _ => return None,
+            },
+        };
+        let reduction = match fields.next()? {
+            "=" => Change::Same,
+            "-" => Change::Different {
+                before: None,
+                after: None,
+            },
+            r => Change::Different {
+                before: None,
+                after: Some(r.parse().ok()?),
+            },
+        };
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            specific_part: AbstractDelta {
+                definition,
+                reduction,
+            },
+            lefthand_of,
+            righthand_of,
+            reduces_from,
+        })
+    }
+}
+
+/// Writes `=` if nothing changes, otherwise the added and the removed indices separated by `/`.
+fn set_change_record(set_change: &SetChange) -> String {
+    if set_change.is_same() {
+        "=".to_string()
+    } else {
+        format!(
+            "{}/{}",
+            ids_record(&set_change.add),
+            ids_record(&set_change.remove)
+        )
+    }
+}
+
+fn set_change_from_record(record: &str) -> Option<SetChange> {
+    if record == "=" {
+        return Some(SetChange::default());
+    }
+    let mut parts = record.split('/');
+    let add = ids_from_record(parts.next()?)?;
+    let remove = ids_from_record(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
     }
+    Some(SetChange {
+        add: add.into_iter().collect(),
+        remove: remove.into_iter().collect(),
+    })
 }
 
 impl Apply for Concept {
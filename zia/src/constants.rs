@@ -25,3 +25,11 @@ pub const LEFT: usize = 8;
 pub const PRECEDENCE: usize = 9;
 pub const DEFAULT: usize = 10;
 pub const GREATER_THAN: usize = 11;
+pub const STEP: usize = 12;
+pub const STEPS: usize = 13;
+pub const EXPAND: usize = 14;
+pub const DEFINITION_OF: usize = 15;
+pub const REDUCTION_OF: usize = 16;
+pub const REDUCES_FROM: usize = 17;
+pub const FORGET: usize = 18;
+pub const ASSUMING: usize = 19;
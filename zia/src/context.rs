@@ -15,42 +15,111 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use ast::SyntaxTree;
+use change_summary::ChangeSummary;
+use concept_details::{ConceptDetails, ConceptKind};
 use concepts::{AbstractPart, Concept};
-use constants::{DEFINE, LABEL, LET, REDUCTION, TRUE};
+use constants::{
+    ASSOC, ASSUMING, DEFINE, DEFINITION_OF, EXPAND, FORGET, LABEL, LET,
+    PRECEDENCE, REDUCES_FROM, REDUCTION, REDUCTION_OF, STEP, STEPS, TRUE,
+};
 use context_delta::{
     update_concept_delta, ConceptDelta, ContextDelta, StringDelta,
 };
 use context_search::ContextSearch;
 use delta::{Apply, Delta};
 use errors::{map_err_variant, ZiaError, ZiaResult};
+use journal;
+use merge::{is_same_concept, MergeConflict};
+use semantic_diff::{compare_rules, SemanticDiff};
 #[cfg(not(target_arch = "wasm32"))]
 use slog::{Drain, Logger};
 use snap_shot::SnapShot;
-use std::{default::Default, iter::from_fn, mem::swap, rc::Rc};
+use std::{
+    collections::BTreeMap,
+    default::Default,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    iter::from_fn,
+    mem::swap,
+    path::Path,
+    rc::Rc,
+    sync::Arc,
+};
+
+/// Reduction rules and labelled definitions, as returned by `reduction_rules` and `labelled_definitions`.
+type RuleMaps = (BTreeMap<String, String>, BTreeMap<String, String>);
+
+/// A function called with the changes each time a context commits them.
+type Observer = Arc<dyn Fn(&ChangeSummary) + Send + Sync>;
 
-#[derive(Clone)]
 pub struct Context {
     snap_shot: SnapShot,
     #[cfg(not(target_arch = "wasm32"))]
     logger: Logger,
     delta: ContextDelta,
+    observers: Vec<Observer>,
+    checkpoints: BTreeMap<String, SnapShot>,
+    journal: Option<File>,
 }
 
 impl Context {
     #[must_use]
     pub fn new() -> Self {
-        let mut cont = Self::default();
-        cont.setup();
+        Self::default().with_built_ins()
+    }
+
+    /// Returns a new context that does not log what it does, for programs that use stdout for something else.
+    #[must_use]
+    pub fn new_without_logging() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            logger: Logger::root(slog::Discard, o!()),
+            ..Self::default()
+        }
+        .with_built_ins()
+    }
+
+    /// Stops the context from logging what it does, such as for a context read by `load_snapshot` or `recover` in a program that uses stdout for something else.
+    #[must_use]
+    pub fn without_logging(self) -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            logger: Logger::root(slog::Discard, o!()),
+            ..self
+        }
+    }
+
+    fn with_built_ins(mut self) -> Self {
+        self.setup();
         #[cfg(not(target_arch = "wasm32"))]
-        info!(cont.logger, "Setup a new context: {:#?}", &cont.delta);
-        cont.commit();
-        cont
+        info!(self.logger, "Setup a new context: {:#?}", &self.delta);
+        self.commit();
+        self
+    }
+
+    /// Returns a new context with the same concepts as this one. Both contexts share the concepts until one of them changes, so forking takes the same time however large this context is and each fork only takes up memory for the concepts it changes. Like a clone, the fork does not call the observers subscribed to this context.
+    #[must_use]
+    pub fn fork(&self) -> Self {
+        Self {
+            snap_shot: self.snap_shot.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            logger: self.logger.clone(),
+            delta: self.delta.clone(),
+            observers: Vec::new(),
+            checkpoints: self.checkpoints.clone(),
+            journal: None,
+        }
     }
 
     pub fn execute(&mut self, command: &str) -> String {
+        self.try_execute(command).unwrap_or_else(|e| e.to_string())
+    }
+
+    /// Executes a command in the same way as `execute` but returns the error, rather than its message, if the command fails.
+    pub fn try_execute(&mut self, command: &str) -> ZiaResult<String> {
         #[cfg(not(target_arch = "wasm32"))]
         info!(self.logger, "execute({})", command);
-        let string = self
+        let result = self
             .snap_shot
             .ast_from_expression(&self.delta, command)
             .and_then(|a| {
@@ -60,18 +129,813 @@ impl Context {
                     "ast_from_expression({}) -> {:#?}", command, a
                 );
                 self.call(&a)
-            })
-            .unwrap_or_else(|e| e.to_string());
+            });
+        if let Err(ZiaError::Internal {
+            ..
+        }) = result
+        {
+            // Changes made before the inconsistency was found cannot be trusted
+            self.delta = ContextDelta::default();
+        }
         #[cfg(not(target_arch = "wasm32"))]
         info!(self.logger, "execute({}) -> {:#?}", command, self.delta);
         self.commit();
-        string
+        // An internal error means the concepts were already inconsistent before the command
+        #[cfg(feature = "integrity_checks")]
+        if !matches!(result, Err(ZiaError::Internal { .. })) {
+            assert_eq!(
+                self.check_integrity(),
+                Vec::<String>::new(),
+                "Integrity of context broken by \"{}\"",
+                command
+            );
+        }
+        result
+    }
+
+    /// Writes every concept of the context to the file at `path`, replacing it, so that the context can be recovered from it. The file is written in full and stored on disk before it replaces the old one.
+    pub fn save_snapshot<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut partial = path.as_ref().as_os_str().to_owned();
+        partial.push(".partial");
+        let mut file = File::create(&partial)?;
+        file.write_all(journal::snapshot(&self.snap_shot).as_bytes())?;
+        file.sync_data()?;
+        fs::rename(&partial, path)
+    }
+
+    /// Appends each change committed from now on to the journal file at `path`, creating it if needed. Clones and forks of the context do not write to the journal.
+    pub fn journal<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let mut file =
+            OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{}", journal::JOURNAL_HEADER)?;
+        }
+        self.journal = Some(file);
+        Ok(())
+    }
+
+    /// Reads a context from a file written by `save_snapshot`.
+    pub fn load_snapshot<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut cont = Self::default();
+        journal::replay(
+            &mut cont.snap_shot,
+            &fs::read_to_string(path)?,
+            journal::SNAPSHOT_HEADER,
+            false,
+        )?;
+        Ok(cont)
+    }
+
+    /// Reads a context from a file written by `save_snapshot` and then makes the changes appended to a journal file since the snapshot was saved. A change that was only partly written, such as when the program writing the journal crashed, is left out. If there is no journal file then the context is read from the snapshot alone.
+    pub fn recover<P: AsRef<Path>, Q: AsRef<Path>>(
+        snapshot: P,
+        journal: Q,
+    ) -> io::Result<Self> {
+        let mut cont = Self::load_snapshot(snapshot)?;
+        match fs::read_to_string(journal) {
+            Ok(text) => journal::replay(
+                &mut cont.snap_shot,
+                &text,
+                journal::JOURNAL_HEADER,
+                true,
+            )?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e),
+        }
+        Ok(cont)
+    }
+
+    /// Saves the concepts of the context under `name` so that they can be restored later, replacing any checkpoint of the same name. Checkpoints share unchanged concepts with the context, so they are cheap to keep.
+    pub fn checkpoint(&mut self, name: &str) {
+        self.checkpoints.insert(name.to_string(), self.snap_shot.clone());
+    }
+
+    /// Changes the context back to how it was when the checkpoint called `name` was made. The checkpoint is kept so it can be restored again. Returns false if there is no such checkpoint.
+    pub fn restore(&mut self, name: &str) -> bool {
+        match self.checkpoints.get(name) {
+            Some(checkpoint) => {
+                self.delta = self.snap_shot.diff(checkpoint.clone());
+                self.commit();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Forgets the checkpoint called `name`. Returns false if there is no such checkpoint.
+    pub fn remove_checkpoint(&mut self, name: &str) -> bool {
+        self.checkpoints.remove(name).is_some()
+    }
+
+    /// Returns the names of the checkpoints in alphabetical order.
+    pub fn checkpoints(&self) -> Vec<&str> {
+        self.checkpoints.keys().map(String::as_str).collect()
+    }
+
+    /// Describes the concept of `expression`, such as a label or a parenthesised composition of labels. Returns `None` if the expression has no concept yet.
+    pub fn inspect(
+        &self,
+        expression: &str,
+    ) -> ZiaResult<Option<ConceptDetails>> {
+        let ast =
+            self.snap_shot.ast_from_expression(&self.delta, expression)?;
+        let id = match ast.get_concept() {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        let concept = self.snap_shot.read_concept(&self.delta, id)?;
+        Ok(Some(ConceptDetails {
+            id,
+            kind: match concept.get_string() {
+                Some(string) => ConceptKind::String(string),
+                None if concept.is_concrete() => ConceptKind::Concrete,
+                None => ConceptKind::Abstract,
+            },
+            definition: self.definition_of(id)?,
+            reduction: self.reduction_of(id)?,
+            reduces_from: self
+                .sorted_syntax(concept.find_what_reduces_to_it())?,
+            lefthand_of: self.sorted_syntax(
+                self.without_labelling(concept.get_lefthand_of().iter()).iter(),
+            )?,
+            righthand_of: self.sorted_syntax(
+                self.without_labelling(concept.get_righthand_of().iter())
+                    .iter(),
+            )?,
+            precedence: self.property_of(PRECEDENCE, &ast)?,
+            associativity: self.property_of(ASSOC, &ast)?,
+            normal_form: self.normal_form(&ast)?,
+        }))
+    }
+
+    /// Leaves out the compositions that label concepts, as they are how labels are stored rather than part of what concepts mean.
+    fn without_labelling<'a, I>(&self, compositions: I) -> Vec<usize>
+    where
+        I: Iterator<Item = &'a usize>,
+    {
+        compositions
+            .cloned()
+            .filter(|&composition| {
+                self.snap_shot
+                    .get_concept(composition)
+                    .and_then(Concept::get_definition)
+                    .is_none_or(|(left, _)| left != LABEL)
+            })
+            .collect()
+    }
+
+    /// Returns every label used in the context, including those of the built-in concepts, in alphabetical order.
+    pub fn labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .snap_shot
+            .string_concepts()
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Returns the labels of the concrete concepts, which are the built-in concepts that give meaning to expressions like `let`, `->` and `:=`, in alphabetical order.
+    pub fn concrete_labels(&self) -> Vec<String> {
+        self.labels()
+            .into_iter()
+            .filter(|label| {
+                match self.snap_shot.concept_from_label(&self.delta, label) {
+                    Ok(Some(concept)) => self
+                        .snap_shot
+                        .get_concept(concept)
+                        .is_some_and(Concept::is_concrete),
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+
+    /// Checks that the relationships between concepts are consistent in both directions, that no two concepts share the same definition, and that strings and variables refer to existing concepts. Returns a description of each violation found.
+    pub fn check_integrity(&self) -> Vec<String> {
+        self.snap_shot.check_integrity()
+    }
+
+    /// Compares the reduction rules and the definitions of labelled concepts with those of another context. Concepts are matched by their labels, or by the labels of the concepts they are composed of, rather than by how they are stored.
+    pub fn diff_semantic(&self, other: &Self) -> ZiaResult<SemanticDiff> {
+        let mut diff = SemanticDiff::default();
+        compare_rules(
+            &mut diff,
+            &self.reduction_rules()?,
+            &other.reduction_rules()?,
+            reduction_command,
+            reduction_removal_command,
+        );
+        compare_rules(
+            &mut diff,
+            &self.labelled_definitions()?,
+            &other.labelled_definitions()?,
+            definition_command,
+            definition_removal_command,
+        );
+        Ok(diff)
+    }
+
+    /// Combines the changes that `ours` and `theirs` each made to this context, their common ancestor. Their changes are read from the difference between this context and `theirs` and made to a copy of `ours`. Concepts that both sides inherited from this context are matched by where they are stored, so they still match after either side relabels them, and new concepts are matched by their strings, definitions or labels. Changes that both sides made differently, or that our side rejects, are returned as conflicts and leave our side's version in place.
+    pub fn merge(
+        &self,
+        ours: &Self,
+        theirs: &Self,
+    ) -> ZiaResult<(Self, Vec<MergeConflict>)> {
+        let our_delta = self.snap_shot.diff(ours.snap_shot.clone());
+        let their_delta = self.snap_shot.diff(theirs.snap_shot.clone());
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+        for (concept, (concept_delta, _, _)) in their_delta.concept {
+            match concept_delta {
+                ConceptDelta::Remove(_) => removed.push(concept),
+                _ => changed.push(concept),
+            }
+        }
+        changed.sort_unstable();
+        removed.sort_unstable();
+        let mut merged = ours.clone();
+        let mut conflicts = Vec::new();
+        for concept in changed {
+            let definition = theirs
+                .snap_shot
+                .read_concept(&theirs.delta, concept)?
+                .get_definition();
+            if let Some((LABEL, labellee)) = definition {
+                conflicts.extend(
+                    merged.merge_label(self, theirs, concept, labellee)?,
+                );
+            } else {
+                conflicts
+                    .extend(merged.merge_definition(self, theirs, concept)?);
+                conflicts
+                    .extend(merged.merge_reduction(self, theirs, concept)?);
+            }
+        }
+        // A concept they forgot is kept if our side changed it or still uses it. Labels and strings are forgotten along with the concepts they belong to.
+        for concept in removed {
+            let forgettable = match merged.snap_shot.get_concept(concept) {
+                Some(c) => {
+                    !our_delta.concept.contains_key(&concept)
+                        && c.is_abstract()
+                        && !matches!(c.get_definition(), Some((LABEL, _)))
+                        && merged.snap_shot.is_unused(&merged.delta, concept)?
+                },
+                None => false,
+            };
+            if forgettable {
+                merged.forget_unused_concept(concept)?;
+                merged.commit();
+            }
+        }
+        Ok((merged, conflicts))
+    }
+
+    /// Merges the change that `theirs` made to the label that `concept` gives to `labellee`.
+    fn merge_label(
+        &mut self,
+        ancestor: &Self,
+        theirs: &Self,
+        concept: usize,
+        labellee: usize,
+    ) -> ZiaResult<Option<MergeConflict>> {
+        let label = theirs.label_string(labellee)?;
+        let inherited = theirs.inherits(ancestor, labellee)?;
+        let before = if inherited {
+            ancestor.label_string(labellee)?
+        } else {
+            None
+        };
+        if label == before {
+            return Ok(None);
+        }
+        let merged_labellee =
+            self.merged_concept(ancestor, theirs, labellee, false)?;
+        let our_label = match merged_labellee {
+            Some(c) => self.label_string(c)?,
+            None => None,
+        };
+        if our_label == label {
+            return Ok(None);
+        }
+        let syntax = if inherited {
+            ancestor.snap_shot.to_unreduced_ast(&ancestor.delta, labellee)?
+        } else {
+            theirs.snap_shot.to_unreduced_ast(&theirs.delta, concept)?
+        }
+        .to_string();
+        if our_label != before {
+            return Ok(Some(MergeConflict::Relabel {
+                concept: match theirs.definition_of(labellee)? {
+                    Some(definition) if !inherited => definition,
+                    _ => syntax,
+                },
+                ours: our_label,
+                theirs: label,
+            }));
+        }
+        let command = match label {
+            Some(ref label) => {
+                if let Some(other) =
+                    self.snap_shot.concept_from_label(&self.delta, label)?
+                {
+                    return Ok(Some(MergeConflict::Label {
+                        label: label.clone(),
+                        ours: self.definition_of(other)?,
+                        theirs: theirs.definition_of(labellee)?,
+                    }));
+                }
+                definition_command(label, &syntax)
+            },
+            None => definition_removal_command(&syntax),
+        };
+        let result = self.relabel_merged(ancestor, theirs, labellee, label);
+        self.commit_merged(result, command)
+    }
+
+    fn relabel_merged(
+        &mut self,
+        ancestor: &Self,
+        theirs: &Self,
+        labellee: usize,
+        label: Option<String>,
+    ) -> ZiaResult<()> {
+        let labellee =
+            self.insert_merged_concept(ancestor, theirs, labellee)?;
+        // A new concept may have been given the label when it was added
+        if self.label_string(labellee)? == label {
+            return Ok(());
+        }
+        if self.label_string(labellee)?.is_some() {
+            self.unlabel(labellee)?;
+        }
+        label.map_or(Ok(()), |label| self.label(labellee, &label))
+    }
+
+    /// Merges the change that `theirs` made to the definition of `concept`. Only the definitions of concepts inherited from `ancestor` can change, as new concepts are matched by their definitions.
+    fn merge_definition(
+        &mut self,
+        ancestor: &Self,
+        theirs: &Self,
+        concept: usize,
+    ) -> ZiaResult<Option<MergeConflict>> {
+        if !theirs.inherits(ancestor, concept)?
+            || !self.inherits(ancestor, concept)?
+        {
+            return Ok(None);
+        }
+        let before = ancestor
+            .snap_shot
+            .read_concept(&ancestor.delta, concept)?
+            .get_definition();
+        let definition = theirs
+            .snap_shot
+            .read_concept(&theirs.delta, concept)?
+            .get_definition();
+        if definition == before {
+            return Ok(None);
+        }
+        let merged_definition = match definition {
+            Some((left, right)) => match (
+                self.merged_concept(ancestor, theirs, left, false)?,
+                self.merged_concept(ancestor, theirs, right, false)?,
+            ) {
+                (Some(left), Some(right)) => Some(Some((left, right))),
+                _ => None,
+            },
+            None => Some(None),
+        };
+        let our_definition =
+            self.snap_shot.read_concept(&self.delta, concept)?.get_definition();
+        if merged_definition == Some(our_definition) {
+            return Ok(None);
+        }
+        let syntax = theirs
+            .snap_shot
+            .to_unreduced_ast(&theirs.delta, concept)?
+            .to_string();
+        if our_definition != before {
+            return Ok(Some(MergeConflict::Label {
+                label: syntax,
+                ours: self.definition_of(concept)?,
+                theirs: theirs.definition_of(concept)?,
+            }));
+        }
+        let command = theirs.definition_of(concept)?.map_or_else(
+            || definition_removal_command(&syntax),
+            |definition| definition_command(&syntax, &definition),
+        );
+        let result = match (before, definition) {
+            (None, Some((left, right))) => {
+                self.insert_merged_concept(ancestor, theirs, left).and_then(
+                    |left| {
+                        let right =
+                            self.insert_merged_concept(ancestor, theirs, right)?;
+                        self.insert_definition(concept, left, right, false)
+                    },
+                )
+            },
+            (Some((left, right)), None) => {
+                self.delete_definition(concept, left, right)
+            },
+            _ => Err(ZiaError::Internal {
+                message: format!(
+                    "Concept {} changed its definition without changing identity",
+                    concept
+                ),
+            }),
+        };
+        self.commit_merged(result, command)
+    }
+
+    /// Merges the change that `theirs` made to the reduction of `concept`.
+    fn merge_reduction(
+        &mut self,
+        ancestor: &Self,
+        theirs: &Self,
+        concept: usize,
+    ) -> ZiaResult<Option<MergeConflict>> {
+        let before = if theirs.inherits(ancestor, concept)? {
+            ancestor
+                .snap_shot
+                .read_concept(&ancestor.delta, concept)?
+                .get_reduction()
+        } else {
+            None
+        };
+        let reduction = theirs
+            .snap_shot
+            .read_concept(&theirs.delta, concept)?
+            .get_reduction();
+        if reduction == before {
+            return Ok(None);
+        }
+        let merged_reduction = match reduction {
+            Some(r) => {
+                self.merged_concept(ancestor, theirs, r, false)?.map(Some)
+            },
+            None => Some(None),
+        };
+        let merged_concept =
+            self.merged_concept(ancestor, theirs, concept, false)?;
+        let our_reduction = match merged_concept {
+            Some(c) => {
+                self.snap_shot.read_concept(&self.delta, c)?.get_reduction()
+            },
+            None => None,
+        };
+        if merged_reduction == Some(our_reduction) {
+            return Ok(None);
+        }
+        let syntax = theirs
+            .snap_shot
+            .to_unreduced_ast(&theirs.delta, concept)?
+            .to_string();
+        if our_reduction != before {
+            return Ok(Some(MergeConflict::Reduction {
+                syntax,
+                ours: match merged_concept {
+                    Some(c) => self.reduction_of(c)?,
+                    None => None,
+                },
+                theirs: theirs.reduction_of(concept)?,
+            }));
+        }
+        let command = theirs.reduction_of(concept)?.map_or_else(
+            || reduction_removal_command(&syntax),
+            |reduction| reduction_command(&syntax, &reduction),
+        );
+        let result = self
+            .insert_merged_concept(ancestor, theirs, concept)
+            .and_then(|concept| match reduction {
+                Some(r) => {
+                    let r = self.insert_merged_concept(ancestor, theirs, r)?;
+                    self.update_reduction(concept, r, false)
+                },
+                None => self.delete_reduction(concept),
+            });
+        self.commit_merged(result, command)
+    }
+
+    /// Commits the changes made to merge a change from their side. If the changes failed they are dropped instead and the failure is returned as a conflict.
+    fn commit_merged(
+        &mut self,
+        result: ZiaResult<()>,
+        command: String,
+    ) -> ZiaResult<Option<MergeConflict>> {
+        let conflict = match result {
+            Ok(()) => None,
+            Err(ZiaError::Internal {
+                message,
+            }) => {
+                return Err(ZiaError::Internal {
+                    message,
+                })
+            },
+            Err(error) => {
+                self.delta = ContextDelta::default();
+                Some(MergeConflict::Rejected {
+                    command,
+                    error,
+                })
+            },
+        };
+        self.commit();
+        Ok(conflict)
+    }
+
+    /// Returns the concept of this context that matches `concept` of `theirs`, where both contexts were changed from `ancestor`. Concepts that both inherited from `ancestor` are matched by index and others by their string, definition or label. A concept without a match is only added if `insert` is true.
+    fn merged_concept(
+        &mut self,
+        ancestor: &Self,
+        theirs: &Self,
+        concept: usize,
+        insert: bool,
+    ) -> ZiaResult<Option<usize>> {
+        if theirs.inherits(ancestor, concept)?
+            && self.inherits(ancestor, concept)?
+        {
+            return Ok(Some(concept));
+        }
+        let their_concept =
+            theirs.snap_shot.read_concept(&theirs.delta, concept)?;
+        let variable = theirs.snap_shot.has_variable(&theirs.delta, concept);
+        if let Some(string) = their_concept.get_string() {
+            return match self.snap_shot.get_string_concept(&self.delta, &string)
+            {
+                None if insert => self.new_string(&string).map(Some),
+                found => Ok(found),
+            };
+        }
+        if let Some((left, right)) = their_concept.get_definition() {
+            let left = self.merged_concept(ancestor, theirs, left, insert)?;
+            let right = self.merged_concept(ancestor, theirs, right, insert)?;
+            return match (left, right) {
+                (Some(left), Some(right)) if insert => self
+                    .find_or_insert_definition(left, right, variable, false)
+                    .map(Some),
+                (Some(left), Some(right)) => {
+                    self.snap_shot.find_definition(&self.delta, left, right)
+                },
+                _ => Ok(None),
+            };
+        }
+        match theirs.label_string(concept)? {
+            Some(label) => {
+                match self.snap_shot.concept_from_label(&self.delta, &label)? {
+                    None if insert => {
+                        self.new_labelled_default(&label).map(Some)
+                    },
+                    found => Ok(found),
+                }
+            },
+            None if insert => {
+                self.new_default::<AbstractPart>(variable).map(Some)
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn insert_merged_concept(
+        &mut self,
+        ancestor: &Self,
+        theirs: &Self,
+        concept: usize,
+    ) -> ZiaResult<usize> {
+        self.merged_concept(ancestor, theirs, concept, true)?.ok_or_else(|| {
+            ZiaError::Internal {
+                message: format!("Concept {} could not be merged", concept),
+            }
+        })
+    }
+
+    /// Whether `concept` is still the concept stored at the same index in `ancestor`.
+    fn inherits(&self, ancestor: &Self, concept: usize) -> ZiaResult<bool> {
+        let after = match self.delta.concept.get(&concept) {
+            Some((ConceptDelta::Remove(_), _, _)) => None,
+            Some(_) => Some(self.snap_shot.read_concept(&self.delta, concept)?),
+            None => self.snap_shot.get_concept(concept).cloned(),
+        };
+        Ok(match (ancestor.snap_shot.get_concept(concept), after) {
+            (Some(before), Some(after)) => is_same_concept(before, &after),
+            _ => false,
+        })
+    }
+
+    /// Returns the label of `concept`, unlike `SnapShot::get_label` which returns the label of what a concept reduces to if it has none.
+    fn label_string(&self, concept: usize) -> ZiaResult<Option<String>> {
+        match self.snap_shot.get_concept_of_label(&self.delta, concept)? {
+            Some(label_concept) => match self
+                .snap_shot
+                .read_concept(&self.delta, label_concept)?
+                .get_reduction()
+            {
+                Some(string) => Ok(self
+                    .snap_shot
+                    .read_concept(&self.delta, string)?
+                    .get_string()),
+                None => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Calls `observer` each time a command changes the labels, reduction rules or definitions of this context. Clones and forks of this context don't call the observers. Changes to concepts that are inconsistent with each other cannot be summarised, so they are logged as a warning instead of calling the observers.
+    pub fn subscribe<F>(&mut self, observer: F)
+    where
+        F: Fn(&ChangeSummary) + Send + Sync + 'static,
+    {
+        self.observers.push(Arc::new(observer));
+    }
+
+    fn reduction_rules(&self) -> ZiaResult<BTreeMap<String, String>> {
+        self.reduction_rules_of(&self.snap_shot.concept_ids())
+    }
+
+    /// Returns the syntax of each of `concepts` that has a reduction rule, other than those that label concepts, paired with the syntax of its reduction.
+    fn reduction_rules_of(
+        &self,
+        concepts: &[usize],
+    ) -> ZiaResult<BTreeMap<String, String>> {
+        let mut rules = BTreeMap::new();
+        for &concept in concepts {
+            let definition = self
+                .snap_shot
+                .read_concept(&self.delta, concept)?
+                .get_definition();
+            if let Some((LABEL, _)) = definition {
+                continue;
+            }
+            if let Some(reduction) = self.reduction_of(concept)? {
+                let syntax =
+                    self.snap_shot.to_unreduced_ast(&self.delta, concept)?;
+                rules.insert(syntax.to_string(), reduction);
+            }
+        }
+        Ok(rules)
+    }
+
+    fn labelled_definitions(&self) -> ZiaResult<BTreeMap<String, String>> {
+        self.labelled_definitions_of(&self.snap_shot.concept_ids())
+    }
+
+    /// Returns the label of each of `concepts` that is labelled and has a definition paired with the syntax of its definition.
+    fn labelled_definitions_of(
+        &self,
+        concepts: &[usize],
+    ) -> ZiaResult<BTreeMap<String, String>> {
+        let mut definitions = BTreeMap::new();
+        for &concept in concepts {
+            if self
+                .snap_shot
+                .get_concept_of_label(&self.delta, concept)?
+                .is_some()
+            {
+                if let Some(definition) = self.definition_of(concept)? {
+                    let label = self
+                        .snap_shot
+                        .to_unreduced_ast(&self.delta, concept)?;
+                    definitions.insert(label.to_string(), definition);
+                }
+            }
+        }
+        Ok(definitions)
+    }
+
+    /// Removes unlabelled concepts that no longer have any effect on the semantics of the context, such as those left behind when reduction rules are removed or concepts are relabelled, as well as the unlabelled concepts that only they were composed of. If `compact` is true, the remaining concepts are then moved to fill the gaps left by removed concepts. Returns the number of concepts removed. If the concepts are found to be inconsistent with each other, nothing is removed and the error is returned.
+    pub fn collect_garbage(&mut self, compact: bool) -> ZiaResult<usize> {
+        if let Err(e) = self.remove_garbage() {
+            self.delta = ContextDelta::default();
+            return Err(e);
+        }
+        let removed = self
+            .delta
+            .concept
+            .values()
+            .filter(|(cd, _, _)| matches!(cd, ConceptDelta::Remove(_)))
+            .count();
+        #[cfg(not(target_arch = "wasm32"))]
+        info!(self.logger, "collect_garbage() -> {:#?}", self.delta);
+        self.commit();
+        if compact {
+            self.snap_shot.compact();
+            self.write_to_journal(journal::COMPACT_ENTRY);
+        }
+        #[cfg(feature = "integrity_checks")]
+        assert_eq!(
+            self.check_integrity(),
+            Vec::<String>::new(),
+            "Integrity of context broken by garbage collection"
+        );
+        Ok(removed)
+    }
+
+    fn remove_garbage(&mut self) -> ZiaResult<()> {
+        for concept in self.snap_shot.concept_ids() {
+            if !self.is_removed(concept) && self.is_garbage(concept)? {
+                self.forget_unused_concept(concept)?;
+            }
+        }
+        for (string, concept) in self.snap_shot.string_concepts() {
+            if !self.is_removed(concept)
+                && self
+                    .snap_shot
+                    .read_concept(&self.delta, concept)?
+                    .find_what_reduces_to_it()
+                    .next()
+                    .is_none()
+            {
+                self.remove_string(&string)?;
+                self.blindly_remove_concept(concept)?;
+            }
+        }
+        Ok(())
     }
 
     fn commit(&mut self) {
         let mut delta_to_apply = ContextDelta::default();
         swap(&mut delta_to_apply, &mut self.delta);
+        let entry = self
+            .journal
+            .as_ref()
+            .map(|_| journal::commit_entry(&delta_to_apply));
+        if self.observers.is_empty() {
+            self.snap_shot.apply(delta_to_apply);
+        } else {
+            self.apply_and_notify(delta_to_apply);
+        }
+        if let Some(entry) = entry {
+            self.write_to_journal(&entry);
+        }
+    }
+
+    /// Appends `entry` to the journal and waits until it is stored on disk, so that committed changes can be recovered after a power cut as well as a crash. If it cannot be written, the journal is no longer written to so that it still describes a state the context was in.
+    fn write_to_journal(&mut self, entry: &str) {
+        if let Some(ref mut file) = self.journal {
+            if let Err(_e) =
+                file.write_all(entry.as_bytes()).and_then(|()| file.sync_data())
+            {
+                #[cfg(not(target_arch = "wasm32"))]
+                warn!(self.logger, "Stopped writing to the journal: {}", _e);
+                self.journal = None;
+            }
+        }
+    }
+
+    /// Applies `delta_to_apply` and calls the observers with a summary of the changes. If the rules of the changed concepts cannot be read, which only happens when the concepts are inconsistent with each other, the changes are still applied but a warning is logged instead of calling the observers.
+    fn apply_and_notify(&mut self, delta_to_apply: ContextDelta) {
+        let mut labels_added = Vec::new();
+        let mut labels_removed = Vec::new();
+        for (string, string_delta) in &delta_to_apply.string {
+            match string_delta {
+                StringDelta::Insert(_) => labels_added.push(string.clone()),
+                StringDelta::Remove(_) => labels_removed.push(string.clone()),
+                StringDelta::Update {
+                    ..
+                } => (),
+            }
+        }
+        labels_added.sort();
+        labels_removed.sort();
+        let mut changed: Vec<usize> =
+            delta_to_apply.concept.keys().cloned().collect();
+        changed.sort();
+        let before = self.rules_of_existing(&changed);
         self.snap_shot.apply(delta_to_apply);
+        let after = self.rules_of_existing(&changed);
+        match (before, after) {
+            (Ok(before), Ok(after)) => {
+                let summary = ChangeSummary::new(
+                    labels_added,
+                    labels_removed,
+                    (&before.0, &after.0),
+                    (&before.1, &after.1),
+                );
+                if !summary.is_empty() {
+                    self.observers
+                        .iter()
+                        .for_each(|observer| observer(&summary));
+                }
+            },
+            (Err(_e), _) | (_, Err(_e)) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                warn!(self.logger, "Observers not called for changes: {}", _e);
+            },
+        }
+    }
+
+    /// Returns the reduction rules and labelled definitions of those of `concepts` that exist.
+    fn rules_of_existing(&self, concepts: &[usize]) -> ZiaResult<RuleMaps> {
+        let existing: Vec<usize> = concepts
+            .iter()
+            .cloned()
+            .filter(|&concept| self.snap_shot.get_concept(concept).is_some())
+            .collect();
+        Ok((
+            self.reduction_rules_of(&existing)?,
+            self.labelled_definitions_of(&existing)?,
+        ))
     }
 
     fn setup(&mut self) {
@@ -81,15 +945,33 @@ impl Context {
                 Concept::default(),
                 false,
             );
-            self.delta.combine(delta);
-            index
+            self.delta.combine(delta)?;
+            Ok(index)
         };
         let labels = vec![
-            "label_of", ":=", "->", "let", "true", "false", "assoc", "right",
-            "left", "prec", "default", ">",
+            "label_of",
+            ":=",
+            "->",
+            "let",
+            "true",
+            "false",
+            "assoc",
+            "right",
+            "left",
+            "prec",
+            "default",
+            ">",
+            "step",
+            "steps",
+            "expand",
+            "definition_of",
+            "reduction_of",
+            "reduces_from",
+            "forget",
+            "assuming",
         ];
         let mut counter = 0;
-        let concepts: Vec<usize> = from_fn(|| {
+        let concepts: ZiaResult<Vec<usize>> = from_fn(|| {
             if counter < labels.len() {
                 counter += 1;
                 Some(concrete_constructor())
@@ -99,9 +981,11 @@ impl Context {
         })
         .collect();
         concepts
-            .iter()
-            .zip(&labels)
-            .try_for_each(|(concept, string)| self.label(*concept, string))
+            .and_then(|concepts| {
+                concepts.iter().zip(&labels).try_for_each(
+                    |(concept, string)| self.label(*concept, string),
+                )
+            })
             .unwrap();
     }
 
@@ -111,9 +995,9 @@ impl Context {
         right: &Rc<SyntaxTree>,
     ) -> ZiaResult<String> {
         let reduced_left =
-            ContextSearch::from((&self.snap_shot, &self.delta)).reduce(left);
-        let reduced_right =
-            ContextSearch::from((&self.snap_shot, &self.delta)).reduce(right);
+            ContextSearch::from((&self.snap_shot, &self.delta)).reduce(left)?;
+        let reduced_right = ContextSearch::from((&self.snap_shot, &self.delta))
+            .reduce(right)?;
         match (reduced_left, reduced_right) {
             (None, None) => Err(ZiaError::CannotReduceFurther),
             (Some(rl), None) => self.call_pair(&rl, right),
@@ -127,7 +1011,7 @@ impl Context {
         &mut self,
         ast: &Rc<SyntaxTree>,
     ) -> ZiaResult<String> {
-        let expansion = &self.snap_shot.expand(&self.delta, ast);
+        let expansion = &self.snap_shot.expand(&self.delta, ast)?;
         if expansion == ast {
             Err(ZiaError::CannotExpandFurther)
         } else {
@@ -141,7 +1025,7 @@ impl Context {
         ast: &Rc<SyntaxTree>,
     ) -> ZiaResult<String> {
         let normal_form = &ContextSearch::from((&self.snap_shot, &self.delta))
-            .recursively_reduce(ast);
+            .recursively_reduce(ast)?;
         if normal_form == ast {
             Err(ZiaError::CannotReduceFurther)
         } else {
@@ -151,9 +1035,13 @@ impl Context {
 
     /// If the associated concept of the syntax tree is a string concept that that associated string is returned. If not, the function tries to expand the syntax tree. If that's possible, `call_pair` is called with the lefthand and righthand syntax parts. If not `try_expanding_then_call` is called on the tree. If a program cannot be found this way, `Err(ZiaError::NotAProgram)` is returned.
     fn call(&mut self, ast: &Rc<SyntaxTree>) -> ZiaResult<String> {
-        match ast.get_concept().and_then(|c| {
-            self.snap_shot.read_concept(&self.delta, c).get_string()
-        }) {
+        let string = match ast.get_concept() {
+            Some(c) => {
+                self.snap_shot.read_concept(&self.delta, c)?.get_string()
+            },
+            None => None,
+        };
+        match string {
             Some(s) => Ok(s),
             None => match ast.get_expansion() {
                 Some((ref left, ref right)) => map_err_variant(
@@ -164,10 +1052,9 @@ impl Context {
                             self.try_reducing_then_call(ast),
                             &ZiaError::CannotReduceFurther,
                             || {
-                                Ok(self
-                                    .snap_shot
+                                self.snap_shot
                                     .contract_pair(&self.delta, left, right)
-                                    .to_string())
+                                    .map(|ast| ast.to_string())
                             },
                         )
                     },
@@ -187,7 +1074,7 @@ impl Context {
         }
     }
 
-    /// If the associated concept of the lefthand part of the syntax tree is LET then `call_as_righthand` is called with the left and right of the lefthand syntax. Tries to get the concept associated with the righthand part of the syntax. If the associated concept is `->` then `call` is called with the reduction of the lefthand part of the syntax. Otherwise `Err(ZiaError::NotAProgram)` is returned.
+    /// If the associated concept of the lefthand part of the syntax tree is LET then `call_as_righthand` is called with the left and right of the lefthand syntax. If it is STEP or STEPS then the righthand part of the syntax is only reduced by a limited number of steps. If it is EXPAND then the righthand part of the syntax is expanded into the leaves of its definitions. If it is DEFINITION_OF, REDUCTION_OF or REDUCES_FROM then the corresponding relationships of the concept associated with the righthand part of the syntax are returned. If it is FORGET then the concept associated with the righthand part of the syntax is removed. If it is ASSUMING then the second part of the righthand syntax is called as if the rule given by the first part had been added. Tries to get the concept associated with the righthand part of the syntax. If the associated concept is `->` then `call` is called with the reduction of the lefthand part of the syntax. Otherwise `Err(ZiaError::NotAProgram)` is returned.
     fn call_pair(
         &mut self,
         left: &Rc<SyntaxTree>,
@@ -195,29 +1082,55 @@ impl Context {
     ) -> ZiaResult<String> {
         left.get_concept()
             .and_then(|lc| match lc {
-                LET => right
-                    .get_expansion()
-                    .and_then(|(left, right)| {
-                        self.execute_let(&left, &right).and_then(|x| match x {
-                            Err(ZiaError::CannotReduceFurther)
-                            | Err(ZiaError::UnusedSymbol) => None,
-                            _ => Some(x),
-                        })
-                    })
-                    .or_else(|| {
-                        Some({
-                            let true_syntax =
-                                self.snap_shot.to_ast(&self.delta, TRUE);
-                            self.execute_reduction(right, &true_syntax)
+                LET => Some(self.let_rule(right).map(|()| "".to_string())),
+                LABEL => Some(
+                    right
+                        .get_concept()
+                        .map_or(Ok(None), |c| {
+                            self.snap_shot.get_label(&self.delta, c)
                         })
-                    })
-                    .map(|r| r.map(|()| "".to_string())),
-                LABEL => Some(Ok("'".to_string()
-                    + &right
+                        .map(|label| {
+                            "'".to_string()
+                                + &label.unwrap_or_else(|| right.to_string())
+                                + "'"
+                        }),
+                ),
+                STEP => Some(self.reduce_by_steps(right, 1)),
+                STEPS => right.get_expansion().map(|(count, expression)| {
+                    let count = count.to_string();
+                    count.parse().map_or_else(
+                        |_| {
+                            Err(ZiaError::InvalidStepCount {
+                                count,
+                            })
+                        },
+                        |steps| self.reduce_by_steps(&expression, steps),
+                    )
+                }),
+                ASSUMING => right
+                    .get_expansion()
+                    .map(|(rule, query)| self.call_assuming(&rule, &query)),
+                EXPAND => Some(
+                    self.snap_shot
+                        .expand(&self.delta, right)
+                        .map(|ast| ast.to_string()),
+                ),
+                DEFINITION_OF => right
+                    .get_concept()
+                    .and_then(|c| self.definition_of(c).transpose()),
+                REDUCTION_OF => right
+                    .get_concept()
+                    .and_then(|c| self.reduction_of(c).transpose()),
+                REDUCES_FROM => right
+                    .get_concept()
+                    .and_then(|c| self.reduces_from(c).transpose()),
+                FORGET => Some(
+                    right
                         .get_concept()
-                        .and_then(|c| self.snap_shot.get_label(&self.delta, c))
-                        .unwrap_or_else(|| right.to_string())
-                    + "'")),
+                        .ok_or(ZiaError::UnusedSymbol)
+                        .and_then(|c| self.forget(c))
+                        .map(|()| "".to_string()),
+                ),
                 _ => None,
             })
             .unwrap_or_else(|| match right.get_concept() {
@@ -226,6 +1139,133 @@ impl Context {
             })
     }
 
+    /// Adds the rule given by the syntax following `let`. Syntax that isn't a reduction rule or a definition is let be true.
+    fn let_rule(&mut self, rule: &Rc<SyntaxTree>) -> ZiaResult<()> {
+        rule.get_expansion()
+            .and_then(|(left, right)| {
+                self.execute_let(&left, &right).and_then(|x| match x {
+                    Err(ZiaError::CannotReduceFurther)
+                    | Err(ZiaError::UnusedSymbol) => None,
+                    _ => Some(x),
+                })
+            })
+            .unwrap_or_else(|| {
+                self.snap_shot.to_ast(&self.delta, TRUE).and_then(
+                    |true_syntax| self.execute_reduction(rule, &true_syntax),
+                )
+            })
+    }
+
+    /// Calls `query` as if `rule` had been added with `let`. Every change made since the last commit, including the concepts and labels created for the rule, is then discarded so none of them are committed.
+    fn call_assuming(
+        &mut self,
+        rule: &Rc<SyntaxTree>,
+        query: &Rc<SyntaxTree>,
+    ) -> ZiaResult<String> {
+        let committable = self.delta.clone();
+        let result = self.let_rule(rule).and_then(|()| {
+            let query = self.snap_shot.bind_concepts(&self.delta, query)?;
+            self.call(&query)
+        });
+        self.delta = committable;
+        result
+    }
+
+    /// Returns the syntax after it has been reduced at most `steps` times.
+    fn reduce_by_steps(
+        &self,
+        ast: &Rc<SyntaxTree>,
+        steps: usize,
+    ) -> ZiaResult<String> {
+        let reduced = ContextSearch::from((&self.snap_shot, &self.delta))
+            .reduce_by_steps(ast, steps)?;
+        Ok(self.snap_shot.unreduce_labels(&self.delta, &reduced)?.to_string())
+    }
+
+    /// Returns the syntax of the lefthand and righthand concepts that compose a concept if it has a definition.
+    fn definition_of(&self, concept: usize) -> ZiaResult<Option<String>> {
+        match self
+            .snap_shot
+            .read_concept(&self.delta, concept)?
+            .get_definition()
+        {
+            Some((left, right)) => Ok(Some(
+                self.snap_shot
+                    .combine(
+                        &self.delta,
+                        &self.snap_shot.to_unreduced_ast(&self.delta, left)?,
+                        &self.snap_shot.to_unreduced_ast(&self.delta, right)?,
+                    )?
+                    .to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the syntax of the concept that a concept directly reduces to if it has a reduction rule.
+    fn reduction_of(&self, concept: usize) -> ZiaResult<Option<String>> {
+        match self.snap_shot.read_concept(&self.delta, concept)?.get_reduction()
+        {
+            Some(reduction) => Ok(Some(
+                self.snap_shot
+                    .to_unreduced_ast(&self.delta, reduction)?
+                    .to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the comma-separated syntax of the concepts that directly reduce to a concept if there are any.
+    fn reduces_from(&self, concept: usize) -> ZiaResult<Option<String>> {
+        let syntax = self.sorted_syntax(
+            self.snap_shot
+                .read_concept(&self.delta, concept)?
+                .find_what_reduces_to_it(),
+        )?;
+        if syntax.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(syntax.join(", ")))
+        }
+    }
+
+    /// Returns the syntax of each of `concepts` in alphabetical order.
+    fn sorted_syntax<'a, I>(&self, concepts: I) -> ZiaResult<Vec<String>>
+    where
+        I: Iterator<Item = &'a usize>,
+    {
+        let mut syntax = concepts
+            .map(|c| {
+                self.snap_shot
+                    .to_unreduced_ast(&self.delta, *c)
+                    .map(|ast| ast.to_string())
+            })
+            .collect::<ZiaResult<Vec<String>>>()?;
+        syntax.sort();
+        Ok(syntax)
+    }
+
+    /// Returns the syntax that `ast` reduces to once no more reduction rules apply.
+    fn normal_form(&self, ast: &Rc<SyntaxTree>) -> ZiaResult<String> {
+        let reduced = ContextSearch::from((&self.snap_shot, &self.delta))
+            .recursively_reduce(ast)?;
+        Ok(self.snap_shot.unreduce_labels(&self.delta, &reduced)?.to_string())
+    }
+
+    /// Returns the normal form of the built-in concept `property` applied to `ast`, such as its precedence.
+    fn property_of(
+        &self,
+        property: usize,
+        ast: &Rc<SyntaxTree>,
+    ) -> ZiaResult<String> {
+        let property_of_ast = self.snap_shot.combine(
+            &self.delta,
+            &self.snap_shot.to_ast(&self.delta, property)?,
+            ast,
+        )?;
+        self.normal_form(&property_of_ast)
+    }
+
     /// If the righthand part of the syntax can be expanded, then `match_righthand_pair` is called. If not, `Err(ZiaError::CannotExpandFurther)` is returned.
     fn execute_let(
         &mut self,
@@ -253,10 +1293,10 @@ impl Context {
                 _ => {
                     let rightleft_reduction = self
                         .snap_shot
-                        .read_concept(&self.delta, c)
+                        .read_concept(&self.delta, c)?
                         .get_reduction();
                     if let Some(r) = rightleft_reduction {
-                        let ast = self.snap_shot.to_ast(&self.delta, r);
+                        let ast = self.snap_shot.to_ast(&self.delta, r)?;
                         self.match_righthand_pair(left, &ast, rightright)
                     } else {
                         Err(ZiaError::CannotReduceFurther)
@@ -295,7 +1335,7 @@ impl Context {
                 (None, Some(b), Some(_)) => {
                     if self
                         .snap_shot
-                        .get_concept_of_label(&self.delta, b)
+                        .get_concept_of_label(&self.delta, b)?
                         .is_none()
                     {
                         self.label(b, &new.to_string())
@@ -328,22 +1368,14 @@ impl Context {
     }
 
     fn cleanly_delete_definition(&mut self, concept: usize) -> ZiaResult<()> {
-        match self.snap_shot.read_concept(&self.delta, concept).get_definition()
+        match self
+            .snap_shot
+            .read_concept(&self.delta, concept)?
+            .get_definition()
         {
             None => Err(ZiaError::RedundantDefinitionRemoval),
             Some((left, right)) => {
-                let concept_delta_array = self
-                    .snap_shot
-                    .read_concept(&self.delta, concept)
-                    .delete_definition(concept);
-                let concept_id_array = [concept, left, right];
-                (0..3).for_each(|index| {
-                    update_concept_delta(
-                        self.delta.concept.entry(concept_id_array[index]),
-                        &concept_delta_array[index],
-                        false,
-                    )
-                });
+                self.delete_definition(concept, left, right)?;
                 self.try_delete_concept(concept)?;
                 self.try_delete_concept(left)?;
                 self.try_delete_concept(right)
@@ -351,43 +1383,163 @@ impl Context {
         }
     }
 
+    fn delete_definition(
+        &mut self,
+        concept: usize,
+        left: usize,
+        right: usize,
+    ) -> ZiaResult<()> {
+        let concept_delta_array = self
+            .snap_shot
+            .read_concept(&self.delta, concept)?
+            .delete_definition(concept);
+        let concept_id_array = [concept, left, right];
+        (0..3).try_for_each(|index| {
+            update_concept_delta(
+                self.delta.concept.entry(concept_id_array[index]),
+                &concept_delta_array[index],
+                false,
+            )
+        })
+    }
+
+    /// Removes an abstract concept that no other concept depends on, along with its label, its rules and any concepts that only it depended on.
+    fn forget(&mut self, concept: usize) -> ZiaResult<()> {
+        if !self.snap_shot.read_concept(&self.delta, concept)?.is_abstract() {
+            Err(ZiaError::ForgettingConcrete)
+        } else if self.snap_shot.is_unused(&self.delta, concept)? {
+            self.forget_unused_concept(concept)
+        } else {
+            Err(ZiaError::ForgettingUsedConcept)
+        }
+    }
+
+    fn forget_unused_concept(&mut self, concept: usize) -> ZiaResult<()> {
+        let mut orphan_candidates = Vec::<usize>::new();
+        let (reduction, definition) = {
+            let c = self.snap_shot.read_concept(&self.delta, concept)?;
+            (c.get_reduction(), c.get_definition())
+        };
+        if let Some(r) = reduction {
+            self.delete_reduction(concept)?;
+            // Like after `let a -> a`, a labelled reduction is kept
+            if self.snap_shot.get_label(&self.delta, r)?.is_none() {
+                orphan_candidates.push(r);
+            }
+        }
+        if let Some((left, right)) = definition {
+            self.delete_definition(concept, left, right)?;
+            orphan_candidates.push(left);
+            orphan_candidates.push(right);
+        }
+        self.remove_label(concept)?;
+        self.blindly_remove_concept(concept)?;
+        orphan_candidates.dedup();
+        orphan_candidates.into_iter().try_for_each(|candidate| {
+            if self.is_orphan(candidate)? {
+                self.forget_unused_concept(candidate)
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// An orphan is an abstract concept without any rule or dependent concept that gives it meaning and that cannot be referred to by a label if it is composed of other concepts.
+    fn is_orphan(&self, concept: usize) -> ZiaResult<bool> {
+        Ok(!self.is_removed(concept)
+            && self.snap_shot.read_concept(&self.delta, concept)?.is_abstract()
+            && (self.snap_shot.is_disconnected(&self.delta, concept)?
+                || (self
+                    .snap_shot
+                    .get_concept_of_label(&self.delta, concept)?
+                    .is_none()
+                    && self
+                        .snap_shot
+                        .read_concept(&self.delta, concept)?
+                        .get_reduction()
+                        .is_none()
+                    && self.snap_shot.is_unused(&self.delta, concept)?)))
+    }
+
+    /// Garbage is an abstract concept without a label, a rule or a dependent concept.
+    fn is_garbage(&self, concept: usize) -> ZiaResult<bool> {
+        let c = self.snap_shot.read_concept(&self.delta, concept)?;
+        Ok(c.is_abstract()
+            && c.get_reduction().is_none()
+            && self.snap_shot.get_label(&self.delta, concept)?.is_none()
+            && self.snap_shot.is_unused(&self.delta, concept)?)
+    }
+
+    fn is_removed(&self, concept: usize) -> bool {
+        matches!(
+            self.delta.concept.get(&concept),
+            Some((ConceptDelta::Remove(_), _, _))
+        )
+    }
+
+    /// Removes the concept that labels a concept as well as the string of the label.
+    fn remove_label(&mut self, concept: usize) -> ZiaResult<()> {
+        if let Some(label_concept) =
+            self.snap_shot.get_concept_of_label(&self.delta, concept)?
+        {
+            let string = self
+                .snap_shot
+                .read_concept(&self.delta, label_concept)?
+                .get_reduction();
+            if string.is_some() {
+                self.delete_reduction(label_concept)?;
+            }
+            self.delete_definition(label_concept, LABEL, concept)?;
+            self.blindly_remove_concept(label_concept)?;
+            if let Some(s) = string {
+                if self
+                    .snap_shot
+                    .read_concept(&self.delta, s)?
+                    .find_what_reduces_to_it()
+                    .next()
+                    .is_none()
+                {
+                    self.remove_concept(s)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn try_delete_concept(&mut self, concept: usize) -> ZiaResult<()> {
-        if self.snap_shot.is_disconnected(&self.delta, concept) {
-            self.unlabel(concept)?;
-            self.remove_concept(concept);
+        if self.snap_shot.is_disconnected(&self.delta, concept)? {
+            self.remove_label(concept)?;
+            self.remove_concept(concept)?;
         }
         Ok(())
     }
 
-    fn remove_concept(&mut self, concept: usize) {
+    fn remove_concept(&mut self, concept: usize) -> ZiaResult<()> {
         if let Some(ref s) =
-            self.snap_shot.read_concept(&self.delta, concept).get_string()
+            self.snap_shot.read_concept(&self.delta, concept)?.get_string()
         {
-            self.remove_string(s);
+            self.remove_string(s)?;
         }
-        self.blindly_remove_concept(concept);
+        self.blindly_remove_concept(concept)
     }
 
-    fn remove_string(&mut self, string: &str) {
-        let index = *(self
-            .delta
-            .string
-            .get(string)
-            .and_then(|sd| match sd {
-                StringDelta::Update {
-                    after,
-                    ..
-                } => Some(after),
-                StringDelta::Insert(index) => Some(index),
-                StringDelta::Remove(_) => None,
-            })
-            .expect("string already removed or doesn't exist"));
+    fn remove_string(&mut self, string: &str) -> ZiaResult<()> {
+        let index = self
+            .snap_shot
+            .get_string_concept(&self.delta, string)
+            .ok_or_else(|| ZiaError::Internal {
+                message: format!(
+                    "String \"{}\" already removed or doesn't exist",
+                    string
+                ),
+            })?;
         self.delta
             .string
             .insert(string.to_string(), StringDelta::Remove(index));
+        Ok(())
     }
 
-    fn blindly_remove_concept(&mut self, id: usize) {
+    fn blindly_remove_concept(&mut self, id: usize) -> ZiaResult<()> {
         let concept = self
             .delta
             .concept
@@ -397,11 +1549,10 @@ impl Context {
                 ConceptDelta::Remove(_) => None,
                 ConceptDelta::Update(_) => self.snap_shot.get_concept(id),
             })
-            .unwrap_or_else(|| {
-                self.snap_shot
-                    .get_concept(id)
-                    .expect("Concept will be already removed!")
-            })
+            .or_else(|| self.snap_shot.get_concept(id))
+            .ok_or_else(|| ZiaError::Internal {
+                message: format!("Concept {} will be already removed", id),
+            })?
             .clone();
         self.delta.concept.insert(
             id,
@@ -411,6 +1562,7 @@ impl Context {
                 false,
             ),
         );
+        Ok(())
     }
 
     fn redefine(
@@ -420,7 +1572,7 @@ impl Context {
         right: &Rc<SyntaxTree>,
     ) -> ZiaResult<()> {
         if let Some((left_concept, right_concept)) =
-            self.snap_shot.read_concept(&self.delta, concept).get_definition()
+            self.snap_shot.read_concept(&self.delta, concept)?.get_definition()
         {
             self.relabel(left_concept, &left.to_string())?;
             self.relabel(right_concept, &right.to_string())
@@ -439,8 +1591,10 @@ impl Context {
     fn unlabel(&mut self, concept: usize) -> ZiaResult<()> {
         let concept_of_label = self
             .snap_shot
-            .get_concept_of_label(&self.delta, concept)
-            .expect("No label to remove");
+            .get_concept_of_label(&self.delta, concept)?
+            .ok_or_else(|| ZiaError::Internal {
+                message: format!("Concept {} has no label to remove", concept),
+            })?;
         self.delete_reduction(concept_of_label)
     }
 
@@ -451,22 +1605,18 @@ impl Context {
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
     ) -> ZiaResult<()> {
-        let new_syntax_tree = left
-            .get_concept()
-            .and_then(|l| {
-                right.get_concept().and_then(|r| {
-                    self.snap_shot.find_definition(&self.delta, l, r).map(
-                        |concept| {
-                            syntax
-                                .parse::<SyntaxTree>()
-                                .unwrap()
-                                .bind_concept(concept)
-                        },
-                    )
-                })
-            })
-            .unwrap_or_else(|| syntax.parse::<SyntaxTree>().unwrap())
-            .bind_pair(left, right);
+        let definition = match (left.get_concept(), right.get_concept()) {
+            (Some(l), Some(r)) => {
+                self.snap_shot.find_definition(&self.delta, l, r)?
+            },
+            _ => None,
+        };
+        let syntax_tree = syntax.parse::<SyntaxTree>().unwrap();
+        let new_syntax_tree = match definition {
+            Some(concept) => syntax_tree.bind_concept(concept),
+            None => syntax_tree,
+        }
+        .bind_pair(left, right);
         self.concept_from_ast(&new_syntax_tree)?;
         Ok(())
     }
@@ -497,16 +1647,15 @@ impl Context {
 
     fn delete_reduction(&mut self, concept_id: usize) -> ZiaResult<()> {
         self.snap_shot
-            .read_concept(&self.delta, concept_id)
-            .remove_reduction(concept_id)
-            .map(|z| {
-                z.iter().for_each(|(id, concept_delta)| {
-                    update_concept_delta(
-                        self.delta.concept.entry(*id),
-                        concept_delta,
-                        false,
-                    )
-                })
+            .read_concept(&self.delta, concept_id)?
+            .remove_reduction(concept_id)?
+            .iter()
+            .try_for_each(|(id, concept_delta)| {
+                update_concept_delta(
+                    self.delta.concept.entry(*id),
+                    concept_delta,
+                    false,
+                )
             })
     }
 
@@ -514,7 +1663,7 @@ impl Context {
         if let Some(c) = ast.get_concept() {
             Ok(c)
         } else if let Some(c) =
-            self.snap_shot.concept_from_label(&self.delta, &ast.to_string())
+            self.snap_shot.concept_from_label(&self.delta, &ast.to_string())?
         {
             Ok(c)
         } else {
@@ -542,7 +1691,8 @@ impl Context {
     }
 
     fn new_labelled_default(&mut self, string: &str) -> ZiaResult<usize> {
-        let new_default = self.new_default::<AbstractPart>(is_variable(string));
+        let new_default =
+            self.new_default::<AbstractPart>(is_variable(string))?;
         self.label(new_default, string)?;
         Ok(new_default)
     }
@@ -551,21 +1701,21 @@ impl Context {
         let variable = is_variable(string);
         let definition =
             self.find_or_insert_definition(LABEL, concept, variable, variable)?;
-        let string_id = self.new_string(string);
+        let string_id = self.new_string(string)?;
         self.update_reduction(definition, string_id, variable)
     }
 
-    fn new_string(&mut self, string: &str) -> usize {
+    fn new_string(&mut self, string: &str) -> ZiaResult<usize> {
         let string_concept = string.to_string().into();
         let (delta, index) = self.snap_shot.add_concept_delta(
             &self.delta,
             string_concept,
             false,
         );
-        self.delta.combine(delta);
+        self.delta.combine(delta)?;
         let string_delta = SnapShot::add_string_delta(index, string);
-        self.delta.combine(string_delta);
-        index
+        self.delta.combine(string_delta)?;
+        Ok(index)
     }
 
     fn find_or_insert_definition(
@@ -576,10 +1726,10 @@ impl Context {
         temporary: bool,
     ) -> ZiaResult<usize> {
         let pair =
-            self.snap_shot.find_definition(&self.delta, lefthand, righthand);
+            self.snap_shot.find_definition(&self.delta, lefthand, righthand)?;
         match pair {
             None => {
-                let definition = self.new_default::<AbstractPart>(variable);
+                let definition = self.new_default::<AbstractPart>(variable)?;
                 self.insert_definition(
                     definition, lefthand, righthand, temporary,
                 )?;
@@ -592,12 +1742,12 @@ impl Context {
     fn new_default<V: Default + Into<Concept>>(
         &mut self,
         variable: bool,
-    ) -> usize {
+    ) -> ZiaResult<usize> {
         let concept: Concept = V::default().into();
         let (delta, index) =
             self.snap_shot.add_concept_delta(&self.delta, concept, variable);
-        self.delta.combine(delta);
-        index
+        self.delta.combine(delta)?;
+        Ok(index)
     }
 
     fn insert_definition(
@@ -607,8 +1757,8 @@ impl Context {
         righthand: usize,
         temporary: bool,
     ) -> ZiaResult<()> {
-        if self.snap_shot.contains(&self.delta, lefthand, definition)
-            || self.snap_shot.contains(&self.delta, righthand, definition)
+        if self.snap_shot.contains(&self.delta, lefthand, definition)?
+            || self.snap_shot.contains(&self.delta, righthand, definition)?
         {
             Err(ZiaError::InfiniteDefinition)
         } else {
@@ -625,9 +1775,9 @@ impl Context {
             let id_array = [definition, lefthand, righthand];
             let concept_delta_array = self
                 .snap_shot
-                .read_concept(&self.delta, definition)
+                .read_concept(&self.delta, definition)?
                 .set_definition(definition, lefthand, righthand)?;
-            concept_delta_array.iter().enumerate().for_each(
+            concept_delta_array.iter().enumerate().try_for_each(
                 |(i, concept_delta)| {
                     update_concept_delta(
                         self.delta.concept.entry(id_array[i]),
@@ -635,8 +1785,7 @@ impl Context {
                         temporary,
                     )
                 },
-            );
-            Ok(())
+            )
         }
     }
 
@@ -646,53 +1795,39 @@ impl Context {
         reduction: usize,
         temporary: bool,
     ) -> ZiaResult<()> {
-        self.snap_shot
-            .get_normal_form(&self.delta, reduction)
-            .and_then(|n| {
-                if concept == n {
-                    Some(Err(ZiaError::CyclicReduction))
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| {
-                self.snap_shot
-                    .read_concept(&self.delta, concept)
-                    .get_reduction()
-                    .and_then(|r| {
-                        if r == reduction {
-                            Some(Err(ZiaError::RedundantReduction))
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or_else(|| {
-                        if reduction
-                            == self.snap_shot.get_reduction_of_composition(
-                                &self.delta,
-                                concept,
-                            )
-                        {
-                            Err(ZiaError::RedundantReduction)
-                        } else {
-                            let concept_deltas = self
-                                .snap_shot
-                                .read_concept(&self.delta, concept)
-                                .reduce_to(concept, reduction)?;
-                            update_concept_delta(
-                                self.delta.concept.entry(concept),
-                                &concept_deltas[0],
-                                temporary,
-                            );
-                            update_concept_delta(
-                                self.delta.concept.entry(reduction),
-                                &concept_deltas[1],
-                                temporary,
-                            );
-                            Ok(())
-                        }
-                    })
-            })
+        let previous_reduction =
+            self.snap_shot.read_concept(&self.delta, concept)?.get_reduction();
+        if self.snap_shot.get_normal_form(&self.delta, reduction)?
+            == Some(concept)
+        {
+            Err(ZiaError::CyclicReduction)
+        } else if previous_reduction == Some(reduction)
+            || reduction
+                == self
+                    .snap_shot
+                    .get_reduction_of_composition(&self.delta, concept)?
+        {
+            Err(ZiaError::RedundantReduction)
+        } else {
+            // The previous reduction should no longer have the concept reducing to it
+            if previous_reduction.is_some() {
+                self.delete_reduction(concept)?;
+            }
+            let concept_deltas = self
+                .snap_shot
+                .read_concept(&self.delta, concept)?
+                .reduce_to(concept, reduction)?;
+            update_concept_delta(
+                self.delta.concept.entry(concept),
+                &concept_deltas[0],
+                temporary,
+            )?;
+            update_concept_delta(
+                self.delta.concept.entry(reduction),
+                &concept_deltas[1],
+                temporary,
+            )
+        }
     }
 }
 
@@ -710,10 +1845,45 @@ impl Default for Context {
             #[cfg(not(target_arch = "wasm32"))]
             logger,
             delta: ContextDelta::default(),
+            observers: Vec::new(),
+            checkpoints: BTreeMap::new(),
+            journal: None,
         }
     }
 }
 
+impl Clone for Context {
+    /// Like a fork, the clone neither calls the observers subscribed to this context nor writes to its journal, so that changes made to the clone are not mistaken for changes to this context.
+    fn clone(&self) -> Self {
+        self.fork()
+    }
+}
+
+fn reduction_command(syntax: &str, reduction: &str) -> String {
+    format!("let {} -> {}", parenthesise(syntax), reduction)
+}
+
+fn reduction_removal_command(syntax: &str) -> String {
+    format!("let {} -> {}", parenthesise(syntax), syntax)
+}
+
+fn definition_command(label: &str, definition: &str) -> String {
+    format!("let {} := {}", label, definition)
+}
+
+fn definition_removal_command(label: &str) -> String {
+    format!("let {} := {}", label, label)
+}
+
+fn parenthesise(syntax: &str) -> String {
+    if syntax.contains(' ') {
+        format!("({})", syntax)
+    } else {
+        syntax.to_string()
+    }
+}
+
+/// Whether a symbol stands for any expression in a reduction rule, such as `_x_`.
 pub fn is_variable(string: &str) -> bool {
     string.starts_with('_') && string.ends_with('_')
 }
@@ -16,6 +16,7 @@
 
 use concepts::{Concept, ConceptDelta as CD};
 use delta::{Apply, Delta};
+use errors::{ZiaError, ZiaResult};
 use std::{
     collections::{hash_map::Entry, HashMap},
     fmt::Debug,
@@ -112,11 +113,18 @@ pub fn update_concept_delta(
     entry: Entry<usize, (ConceptDelta, bool, bool)>,
     concept_delta: &CD,
     temporary: bool,
-) {
+) -> ZiaResult<()> {
+    let mut result = Ok(());
     entry
-        .and_modify(|(cd, _, _)| match cd {
+        .and_modify(|(cd, _, t)| match cd {
+            // Temporary changes are dropped rather than committed alongside permanent changes
+            ConceptDelta::Update(d) if *t && !temporary => {
+                *d = concept_delta.clone();
+                *t = false;
+            },
+            ConceptDelta::Update(_) if !*t && temporary => (),
             ConceptDelta::Update(d) => {
-                d.combine(concept_delta.clone());
+                result = d.combine(concept_delta.clone());
                 *cd = ConceptDelta::Update(d.clone());
             },
             ConceptDelta::Insert(c) => {
@@ -124,7 +132,9 @@ pub fn update_concept_delta(
                 *cd = ConceptDelta::Insert(c.clone());
             },
             ConceptDelta::Remove(_) => {
-                panic!("Concept will already be removed")
+                result = Err(ZiaError::Internal {
+                    message: "Concept will already be removed".to_string(),
+                })
             },
         })
         .or_insert((
@@ -132,11 +142,13 @@ pub fn update_concept_delta(
             false,
             temporary,
         ));
+    result
 }
 
 impl Delta for ContextDelta {
-    fn combine(&mut self, other: Self) {
+    fn combine(&mut self, other: Self) -> ZiaResult<()> {
         for (other_key, (other_value, v2, temporary)) in other.concept {
+            let mut result = Ok(());
             let mut remove_key = false;
             let mut update_delta = None;
             self.concept
@@ -146,7 +158,7 @@ impl Delta for ContextDelta {
                         if c1 == c2 && *v1 == v2 =>
                     {
                         remove_key = true;
-                    }
+                    },
                     (ConceptDelta::Remove(c1), ConceptDelta::Insert(c2)) => {
                         if c1 == c2 {
                             remove_key = true;
@@ -159,14 +171,17 @@ impl Delta for ContextDelta {
                         *v1 = v2;
                     },
                     (ConceptDelta::Update(cd1), ConceptDelta::Update(cd2)) => {
-                        cd1.combine(cd2.clone());
+                        result = cd1.combine(cd2.clone());
                         *v1 = v2;
                     },
-                    _ => panic!(
-                        "Something went wrong when combining concept deltas!"
-                    ),
+                    _ => {
+                        result = Err(ZiaError::Internal {
+                            message: "Something went wrong when combining concept deltas!".to_string(),
+                        })
+                    },
                 })
                 .or_insert((other_value, v2, temporary));
+            result?;
             if remove_key {
                 self.concept.remove(&other_key);
             }
@@ -178,6 +193,7 @@ impl Delta for ContextDelta {
             });
         }
         for (other_key, other_sd) in other.string {
+            let mut result = Ok(());
             let mut remove_string = false;
             let mut sd_to_update = None;
             self.string
@@ -187,7 +203,7 @@ impl Delta for ContextDelta {
                         if u1 == u2 =>
                     {
                         remove_string = true;
-                    }
+                    },
                     (StringDelta::Remove(u1), StringDelta::Insert(u2)) => {
                         if u1 == u2 {
                             remove_string = true;
@@ -219,15 +235,19 @@ impl Delta for ContextDelta {
                     ) if a1 == b2 => {
                         *a1 = *a2;
                     },
-                    _ => panic!(
-                        "Something went wrong when combining string deltas!"
-                    ),
+                    _ => result = Err(ZiaError::Internal {
+                        message:
+                            "Something went wrong when combining string deltas!"
+                                .to_string(),
+                    }),
                 })
                 .or_insert(other_sd);
+            result?;
             if remove_string {
                 self.string.remove(&other_key);
             }
             sd_to_update.map(|sd| self.string.insert(other_key, sd));
         }
+        Ok(())
     }
 }
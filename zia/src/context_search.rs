@@ -17,6 +17,7 @@
 use ast::SyntaxTree;
 use constants::{ASSOC, DEFAULT, FALSE, PRECEDENCE, REDUCTION, RIGHT, TRUE};
 use context_delta::ContextDelta;
+use errors::ZiaResult;
 use snap_shot::SnapShot;
 use std::{
     collections::{HashMap, HashSet},
@@ -32,23 +33,35 @@ pub struct ContextSearch<'a> {
 
 impl<'a> ContextSearch<'a> {
     /// Returns the syntax for the reduction of a concept.
-    fn reduce_concept(&self, id: usize) -> Option<Rc<SyntaxTree>> {
-        let concept = self.snap_shot.read_concept(self.delta, id);
-        concept.get_reduction().and_then(|n| {
-            if self.is_leaf_variable(n) {
-                self.variable_mask.get(&n).cloned()
-            } else {
-                Some(self.snap_shot.to_ast(self.delta, n))
-            }
-        })
+    fn reduce_concept(&self, id: usize) -> ZiaResult<Option<Rc<SyntaxTree>>> {
+        match self.snap_shot.read_concept(self.delta, id)?.get_reduction() {
+            Some(n) => {
+                if self.is_leaf_variable(n)? {
+                    Ok(self.variable_mask.get(&n).cloned())
+                } else {
+                    self.snap_shot.to_ast(self.delta, n).map(Some)
+                }
+            },
+            None => Ok(None),
+        }
     }
 
     /// Reduces the syntax by using the reduction rules of associated concepts.
-    pub fn reduce(&self, ast: &Rc<SyntaxTree>) -> Option<Rc<SyntaxTree>> {
-        ast.get_concept().and_then(|c| self.reduce_concept(c)).or_else(|| {
-            ast.get_expansion()
-                .and_then(|(ref left, ref right)| self.reduce_pair(left, right))
-        })
+    pub fn reduce(
+        &self,
+        ast: &Rc<SyntaxTree>,
+    ) -> ZiaResult<Option<Rc<SyntaxTree>>> {
+        let reduced_concept = match ast.get_concept() {
+            Some(c) => self.reduce_concept(c)?,
+            None => None,
+        };
+        match (reduced_concept, ast.get_expansion()) {
+            (Some(reduction), _) => Ok(Some(reduction)),
+            (None, Some((ref left, ref right))) => {
+                self.reduce_pair(left, right)
+            },
+            (None, None) => Ok(None),
+        }
     }
 
     // Reduces a syntax tree based on the properties of the left and right branches
@@ -56,125 +69,115 @@ impl<'a> ContextSearch<'a> {
         &self,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> Option<Rc<SyntaxTree>> {
-        left.get_concept()
-            .and_then(|lc| match lc {
-                ASSOC => Some(self.snap_shot.to_ast(self.delta, RIGHT)),
-                PRECEDENCE
-                    if right
-                        .get_concept()
-                        .and_then(|c| {
-                            self.snap_shot
-                                .find_definition(self.delta, PRECEDENCE, c)
-                        })
-                        .is_none() =>
-                {
-                    Some(self.snap_shot.to_ast(self.delta, DEFAULT))
-                },
-                _ => {
-                    self.variable_mask.get(&lc).and_then(|ast| self.reduce(ast))
-                },
-            })
-            .or_else(|| {
-                right
-                    .get_expansion()
-                    .and_then(|(ref rightleft, ref rightright)| {
-                        self.reduce_by_expanded_right_branch(
-                            left, rightleft, rightright,
-                        )
-                    })
-                    .or_else(|| self.recursively_reduce_pair(left, right))
-            })
+    ) -> ZiaResult<Option<Rc<SyntaxTree>>> {
+        let reduced_by_left = match left.get_concept() {
+            Some(ASSOC) => Some(self.snap_shot.to_ast(self.delta, RIGHT)?),
+            Some(PRECEDENCE)
+                if match right.get_concept() {
+                    Some(c) => self
+                        .snap_shot
+                        .find_definition(self.delta, PRECEDENCE, c)?
+                        .is_none(),
+                    None => true,
+                } =>
+            {
+                Some(self.snap_shot.to_ast(self.delta, DEFAULT)?)
+            },
+            Some(lc) => match self.variable_mask.get(&lc) {
+                Some(ast) => self.reduce(ast)?,
+                None => None,
+            },
+            None => None,
+        };
+        if reduced_by_left.is_some() {
+            return Ok(reduced_by_left);
+        }
+        let reduced_by_right = match right.get_expansion() {
+            Some((ref rightleft, ref rightright)) => self
+                .reduce_by_expanded_right_branch(left, rightleft, rightright)?,
+            None => None,
+        };
+        if reduced_by_right.is_some() {
+            Ok(reduced_by_right)
+        } else {
+            self.recursively_reduce_pair(left, right)
+        }
     }
 
     fn recursively_reduce_pair(
         &self,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> Option<Rc<SyntaxTree>> {
-        let left_result = self.reduce(left);
-        let right_result = self.reduce(right);
+    ) -> ZiaResult<Option<Rc<SyntaxTree>>> {
+        let left_result = self.reduce(left)?;
+        let right_result = self.reduce(right)?;
         let maybe_subbed_r =
             right.get_concept().and_then(|r| self.variable_mask.get(&r));
         let maybe_subbed_l =
             left.get_concept().and_then(|l| self.variable_mask.get(&l));
         if let (None, None) = (&left_result, &right_result) {
-            self.filter_generalisations_for_pair(left, right)
-                .iter()
-                .filter_map(|(generalisation, variable_to_syntax)| {
-                    let mut context_search = self.clone();
-                    context_search
-                        .variable_mask
-                        .extend(variable_to_syntax.clone());
-                    let gen_ast = context_search
-                        .snap_shot
-                        .to_ast(self.delta, *generalisation);
-                    context_search
-                        .reduce(&gen_ast)
-                        .map(|ast| context_search.substitute(&ast))
-                })
-                .nth(0)
+            for (generalisation, variable_to_syntax) in
+                self.filter_generalisations_for_pair(left, right)?
+            {
+                let mut context_search = self.clone();
+                context_search.variable_mask.extend(variable_to_syntax);
+                let gen_ast = context_search
+                    .snap_shot
+                    .to_ast(self.delta, generalisation)?;
+                if let Some(ast) = context_search.reduce(&gen_ast)? {
+                    return context_search.substitute(&ast).map(Some);
+                }
+            }
+            Ok(None)
         } else {
             let l = left_result
                 .unwrap_or_else(|| maybe_subbed_l.unwrap_or(left).clone());
             let r = right_result
                 .unwrap_or_else(|| maybe_subbed_r.unwrap_or(right).clone());
-            Some(self.snap_shot.contract_pair(self.delta, &l, &r))
+            self.snap_shot.contract_pair(self.delta, &l, &r).map(Some)
         }
     }
 
-    fn substitute(&self, ast: &Rc<SyntaxTree>) -> Rc<SyntaxTree> {
-        ast.get_concept()
-            .and_then(|c| self.variable_mask.get(&c).cloned())
-            .unwrap_or_else(|| {
-                ast.get_expansion().map_or_else(
-                    || ast.clone(),
-                    |(l, r)| {
-                        self.snap_shot.contract_pair(
-                            self.delta,
-                            &self.substitute(&l),
-                            &self.substitute(&r),
-                        )
-                    },
-                )
-            })
+    fn substitute(&self, ast: &Rc<SyntaxTree>) -> ZiaResult<Rc<SyntaxTree>> {
+        match ast.get_concept().and_then(|c| self.variable_mask.get(&c)) {
+            Some(substitution) => Ok(substitution.clone()),
+            None => match ast.get_expansion() {
+                Some((l, r)) => self.snap_shot.contract_pair(
+                    self.delta,
+                    &self.substitute(&l)?,
+                    &self.substitute(&r)?,
+                ),
+                None => Ok(ast.clone()),
+            },
+        }
     }
 
     fn filter_generalisations_for_pair(
         &self,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> Vec<(usize, VariableMask)> {
-        let generalisation_candidates = self.find_generalisations(
-            &self.snap_shot.contract_pair(self.delta, left, right),
-        );
-        generalisation_candidates
-            .iter()
-            .filter_map(|gc| {
-                self.check_generalisation(
-                    &self.snap_shot.contract_pair(self.delta, left, right),
-                    *gc,
-                )
-                .and_then(|vm| {
-                    if vm.is_empty() {
-                        None
-                    } else {
-                        Some((*gc, vm))
-                    }
-                })
-            })
-            .collect()
+    ) -> ZiaResult<Vec<(usize, VariableMask)>> {
+        let ast = self.snap_shot.contract_pair(self.delta, left, right)?;
+        let mut generalisations = Vec::new();
+        for gc in self.find_generalisations(&ast)? {
+            if let Some(vm) = self.check_generalisation(&ast, gc)? {
+                if !vm.is_empty() {
+                    generalisations.push((gc, vm));
+                }
+            }
+        }
+        Ok(generalisations)
     }
 
     fn check_generalisation(
         &self,
         ast: &Rc<SyntaxTree>,
         generalisation: usize,
-    ) -> Option<VariableMask> {
+    ) -> ZiaResult<Option<VariableMask>> {
         if self.is_free_variable(generalisation) {
             if let Some((gl, gr)) = self
                 .snap_shot
-                .read_concept(self.delta, generalisation)
+                .read_concept(self.delta, generalisation)?
                 .get_definition()
             {
                 if let Some((l, r)) = ast.get_expansion() {
@@ -182,91 +185,92 @@ impl<'a> ContextSearch<'a> {
                     let gen_right_var = self.is_free_variable(gr);
                     if gen_left_var && gen_right_var {
                         if let (Some(lm), Some(mut rm)) = (
-                            self.check_generalisation(&l, gl),
-                            self.check_generalisation(&r, gr),
+                            self.check_generalisation(&l, gl)?,
+                            self.check_generalisation(&r, gr)?,
                         ) {
                             for (lmk, lmv) in lm {
                                 if let Some(rmv) = rm.get(&lmk) {
                                     if rmv != &lmv {
-                                        return None;
+                                        return Ok(None);
                                     }
                                 } else {
                                     rm.insert(lmk, lmv);
                                 }
                             }
-                            Some(rm)
+                            Ok(Some(rm))
                         } else {
-                            None
+                            Ok(None)
                         }
                     } else if gen_left_var {
                         if r.get_concept().map_or(false, |c| c == gr) {
                             self.check_generalisation(&l, gl)
                         } else {
-                            None
+                            Ok(None)
                         }
                     } else if gen_right_var {
                         if l.get_concept().map_or(false, |c| c == gl) {
                             self.check_generalisation(&r, gr)
                         } else {
-                            None
+                            Ok(None)
                         }
                     } else if l.get_concept().map_or(false, |c| c == gl)
                         && r.get_concept().map_or(false, |c| c == gr)
                     {
-                        Some(hashmap! {})
+                        Ok(Some(hashmap! {}))
                     } else {
-                        None
+                        Ok(None)
                     }
                 } else {
-                    None
+                    Ok(None)
                 }
             } else {
-                Some(hashmap! {generalisation => ast.clone()})
+                Ok(Some(hashmap! {generalisation => ast.clone()}))
             }
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn find_generalisations(&self, ast: &Rc<SyntaxTree>) -> HashSet<usize> {
+    fn find_generalisations(
+        &self,
+        ast: &Rc<SyntaxTree>,
+    ) -> ZiaResult<HashSet<usize>> {
         let mut generalisations = HashSet::new();
         if let Some((l, r)) = ast.get_expansion() {
             if let Some(c) = l.get_concept() {
                 generalisations.extend(
                     self.snap_shot
-                        .read_concept(self.delta, c)
+                        .read_concept(self.delta, c)?
                         .get_lefthand_of(),
                 );
             }
             if let Some(c) = r.get_concept() {
                 generalisations.extend(
                     self.snap_shot
-                        .read_concept(self.delta, c)
+                        .read_concept(self.delta, c)?
                         .get_righthand_of(),
                 );
             }
-            self.find_generalisations(&l).iter().for_each(|g| {
+            for g in self.find_generalisations(&l)? {
                 generalisations.extend(
                     self.snap_shot
-                        .read_concept(self.delta, *g)
+                        .read_concept(self.delta, g)?
                         .get_lefthand_of(),
                 )
-            });
-            self.find_generalisations(&r).iter().for_each(|g| {
+            }
+            for g in self.find_generalisations(&r)? {
                 generalisations.extend(
                     self.snap_shot
-                        .read_concept(self.delta, *g)
+                        .read_concept(self.delta, g)?
                         .get_righthand_of(),
                 )
-            });
-            generalisations
-        } else {
-            generalisations
+            }
         }
+        Ok(generalisations)
     }
 
-    fn is_leaf_variable(&self, lv: usize) -> bool {
-        self.is_free_variable(lv) && self.is_leaf_concept(lv)
+    fn is_leaf_variable(&self, lv: usize) -> ZiaResult<bool> {
+        Ok(self.is_free_variable(lv) && self.is_leaf_concept(lv)?)
     }
 
     fn is_free_variable(&self, v: usize) -> bool {
@@ -274,15 +278,38 @@ impl<'a> ContextSearch<'a> {
             && self.variable_mask.get(&v).is_none()
     }
 
-    fn is_leaf_concept(&self, l: usize) -> bool {
-        self.snap_shot.read_concept(self.delta, l).get_definition().is_none()
+    fn is_leaf_concept(&self, l: usize) -> ZiaResult<bool> {
+        Ok(self
+            .snap_shot
+            .read_concept(self.delta, l)?
+            .get_definition()
+            .is_none())
     }
 
     /// Reduces the syntax as much as possible (returns the normal form syntax).
-    pub fn recursively_reduce(&self, ast: &Rc<SyntaxTree>) -> Rc<SyntaxTree> {
-        match self.reduce(ast) {
+    pub fn recursively_reduce(
+        &self,
+        ast: &Rc<SyntaxTree>,
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        match self.reduce(ast)? {
             Some(ref a) => self.recursively_reduce(a),
-            None => ast.clone(),
+            None => Ok(ast.clone()),
+        }
+    }
+
+    /// Reduces the syntax at most `steps` times.
+    pub fn reduce_by_steps(
+        &self,
+        ast: &Rc<SyntaxTree>,
+        steps: usize,
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        if steps == 0 {
+            Ok(ast.clone())
+        } else {
+            match self.reduce(ast)? {
+                Some(ref a) => self.reduce_by_steps(a, steps - 1),
+                None => Ok(ast.clone()),
+            }
         }
     }
 
@@ -292,32 +319,37 @@ impl<'a> ContextSearch<'a> {
         left: &Rc<SyntaxTree>,
         rightleft: &Rc<SyntaxTree>,
         rightright: &Rc<SyntaxTree>,
-    ) -> Option<Rc<SyntaxTree>> {
-        rightleft.get_concept().and_then(|rlc| match rlc {
-            REDUCTION => {
-                self.determine_reduction_truth(left, rightright).map(|x| {
-                    if x {
-                        self.snap_shot.to_ast(self.delta, TRUE)
-                    } else {
-                        self.snap_shot.to_ast(self.delta, FALSE)
-                    }
-                })
+    ) -> ZiaResult<Option<Rc<SyntaxTree>>> {
+        match rightleft.get_concept() {
+            Some(REDUCTION) => {
+                match self.determine_reduction_truth(left, rightright)? {
+                    Some(true) => {
+                        self.snap_shot.to_ast(self.delta, TRUE).map(Some)
+                    },
+                    Some(false) => {
+                        self.snap_shot.to_ast(self.delta, FALSE).map(Some)
+                    },
+                    None => Ok(None),
+                }
             },
-            _ => None,
-        })
+            _ => Ok(None),
+        }
     }
 
     fn determine_reduction_truth(
         &self,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> Option<bool> {
+    ) -> ZiaResult<Option<bool>> {
         if left == right {
-            Some(false)
+            Ok(Some(false))
         } else {
-            self.determine_evidence_of_reduction(left, right).or_else(|| {
-                self.determine_evidence_of_reduction(right, left).map(|x| !x)
-            })
+            match self.determine_evidence_of_reduction(left, right)? {
+                Some(x) => Ok(Some(x)),
+                None => Ok(self
+                    .determine_evidence_of_reduction(right, left)?
+                    .map(|x| !x)),
+            }
         }
     }
 
@@ -325,14 +357,14 @@ impl<'a> ContextSearch<'a> {
         &self,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> Option<bool> {
-        self.reduce(left).and_then(|reduced_left| {
-            if &reduced_left == right {
-                Some(true)
-            } else {
+    ) -> ZiaResult<Option<bool>> {
+        match self.reduce(left)? {
+            Some(ref reduced_left) if reduced_left == right => Ok(Some(true)),
+            Some(reduced_left) => {
                 self.determine_evidence_of_reduction(&reduced_left, right)
-            }
-        })
+            },
+            None => Ok(None),
+        }
     }
 }
 
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use errors::{ZiaError, ZiaResult};
 use std::collections::HashSet;
 
 pub trait Apply {
@@ -50,7 +51,7 @@ where
     }
 }
 pub trait Delta {
-    fn combine(&mut self, Self);
+    fn combine(&mut self, Self) -> ZiaResult<()>;
 }
 
 #[derive(Clone, Debug)]
@@ -72,9 +73,9 @@ impl<T> Change<T>
 where
     T: PartialEq,
 {
-    pub fn combine(self, other: Self) -> Self {
+    pub fn combine(self, other: Self) -> ZiaResult<Self> {
         match (self, other) {
-            (Self::Same, x) | (x, Self::Same) => x,
+            (Self::Same, x) | (x, Self::Same) => Ok(x),
             (
                 Self::Different {
                     after: y1,
@@ -86,12 +87,14 @@ where
                 },
             ) => {
                 if y1 == y2 {
-                    Self::Different {
+                    Ok(Self::Different {
                         before: x,
                         after: z,
-                    }
+                    })
                 } else {
-                    panic!("Deltas do not align")
+                    Err(ZiaError::Internal {
+                        message: "Deltas do not align".to_string(),
+                    })
                 }
             },
         }
@@ -137,7 +140,7 @@ impl std::fmt::Debug for SetChange {
 }
 
 impl Delta for SetChange {
-    fn combine(&mut self, other: Self) {
+    fn combine(&mut self, other: Self) -> ZiaResult<()> {
         other.remove.iter().for_each(|item| {
             if self.add.contains(item) {
                 self.add.remove(item);
@@ -152,6 +155,7 @@ impl Delta for SetChange {
                 self.add.insert(*item);
             }
         });
+        Ok(())
     }
 }
 
@@ -99,9 +99,70 @@ pub enum ZiaError {
     /// When trying to specify a reduction rule for a concept whose components reduce to something else.
     #[snafu(display("Concept is already composed of concepts with their own reduction rules."))]
     MultipleReductionPaths,
+    /// When trying to forget a concrete concept.
+    #[snafu(display("Cannot forget a concrete concept"))]
+    ForgettingConcrete,
+    /// When trying to forget a concept that other concepts are composed of or reduce to.
+    #[snafu(display(
+        "Cannot forget a concept that other concepts depend on."
+    ))]
+    ForgettingUsedConcept,
     /// When symbol is expected to be used by a concept but isn't.
     #[snafu(display(
         "Symbol was expected to be used to label a concept but isn't."
     ))]
     UnusedSymbol,
+    /// When the number of steps given to `steps` is not a non-negative integer.
+    #[snafu(display(
+        "The number of steps must be a non-negative integer, not {}.",
+        count
+    ))]
+    InvalidStepCount {
+        count: String,
+    },
+    /// When the concepts of the context are found to be inconsistent with each other.
+    #[snafu(display("Internal error: {}", message))]
+    Internal {
+        message: String,
+    },
+}
+
+impl ZiaError {
+    /// The name of the variant, which unlike the message of the error stays the same between versions.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            ZiaError::RedundantReduction => "RedundantReduction",
+            ZiaError::RedundantDefinition => "RedundantDefinition",
+            ZiaError::RedundantRefactor => "RedundantRefactor",
+            ZiaError::RedundantDefinitionRemoval => {
+                "RedundantDefinitionRemoval"
+            },
+            ZiaError::BadDefinition => "BadDefinition",
+            ZiaError::CyclicReduction => "CyclicReduction",
+            ZiaError::CannotExpandFurther => "CannotExpandFurther",
+            ZiaError::CannotReduceFurther => "CannotReduceFurther",
+            ZiaError::ExpandingReduction => "ExpandingReduction",
+            ZiaError::MissingSymbol {
+                ..
+            } => "MissingSymbol",
+            ZiaError::InfiniteDefinition => "InfiniteDefinition",
+            ZiaError::EmptyParentheses => "EmptyParentheses",
+            ZiaError::AmbiguousExpression => "AmbiguousExpression",
+            ZiaError::DefinitionCollision => "DefinitionCollision",
+            ZiaError::SettingDefinitionOfConcrete => {
+                "SettingDefinitionOfConcrete"
+            },
+            ZiaError::ConcreteReduction => "ConcreteReduction",
+            ZiaError::MultipleReductionPaths => "MultipleReductionPaths",
+            ZiaError::ForgettingConcrete => "ForgettingConcrete",
+            ZiaError::ForgettingUsedConcept => "ForgettingUsedConcept",
+            ZiaError::UnusedSymbol => "UnusedSymbol",
+            ZiaError::InvalidStepCount {
+                ..
+            } => "InvalidStepCount",
+            ZiaError::Internal {
+                ..
+            } => "Internal",
+        }
+    }
 }
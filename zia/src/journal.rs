@@ -0,0 +1,200 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+// Journals and snapshot files are made of lines. The first line names the kind
+// of file and the version of the format. Each committed change follows as a
+// `commit` line, one line for each concept or string that it changes and an
+// `end` line. An added concept is `insert <id> <variable> <record>` where the
+// record is written by `Concept::to_record`, a changed concept is `update <id>
+// <record>` where the record is written by `ConceptDelta::to_record`, a
+// removed concept is `remove <id>`, an added or moved string is `string <id>
+// <string>` and a removed string is `unstring <string>`. Compacting the
+// concepts is written as a `compact` line. A snapshot file holds a single
+// commit that inserts every concept and string.
+
+use concepts::{Concept, ConceptDelta as CD};
+use context_delta::{ConceptDelta, ContextDelta, StringDelta};
+use delta::Apply;
+use snap_shot::SnapShot;
+use std::io::{Error, ErrorKind, Result};
+
+pub const SNAPSHOT_HEADER: &str = "zia snapshot 1";
+pub const JOURNAL_HEADER: &str = "zia journal 1";
+pub const COMPACT_ENTRY: &str = "compact\n";
+
+/// Writes the changes of `delta` that will be applied as a single commit, or nothing if there are none. Temporary changes are left out as they are never applied.
+pub fn commit_entry(delta: &ContextDelta) -> String {
+    let mut strings: Vec<(&String, &StringDelta)> =
+        delta.string.iter().collect();
+    strings.sort_by_key(|(string, _)| *string);
+    let mut concepts: Vec<(&usize, &(ConceptDelta, bool, bool))> = delta
+        .concept
+        .iter()
+        .filter(|(_, (_, _, temporary))| !temporary)
+        .collect();
+    concepts.sort_by_key(|(id, _)| *id);
+    if strings.is_empty() && concepts.is_empty() {
+        return String::new();
+    }
+    let mut entry = "commit\n".to_string();
+    for (string, string_delta) in strings {
+        entry += &match string_delta {
+            StringDelta::Insert(id)
+            | StringDelta::Update {
+                after: id,
+                ..
+            } => format!("string {} {}\n", id, string),
+            StringDelta::Remove(_) => format!("unstring {}\n", string),
+        };
+    }
+    for (id, (concept_delta, variable, _)) in concepts {
+        entry += &match concept_delta {
+            ConceptDelta::Insert(concept) => {
+                insert_record(*id, *variable, concept)
+            },
+            ConceptDelta::Update(changes) => {
+                format!("update {} {}\n", id, changes.to_record())
+            },
+            ConceptDelta::Remove(_) => format!("remove {}\n", id),
+        };
+    }
+    entry + "end\n"
+}
+
+fn insert_record(id: usize, variable: bool, concept: &Concept) -> String {
+    format!(
+        "insert {} {} {}\n",
+        id,
+        if variable {
+            1
+        } else {
+            0
+        },
+        concept.to_record()
+    )
+}
+
+/// Writes every concept and string of `snap_shot` in the format of a snapshot file.
+pub fn snapshot(snap_shot: &SnapShot) -> String {
+    let no_changes = ContextDelta::default();
+    let mut strings = snap_shot.string_concepts();
+    strings.sort();
+    let mut text = format!("{}\ncommit\n", SNAPSHOT_HEADER);
+    for (string, id) in strings {
+        text += &format!("string {} {}\n", id, string);
+    }
+    for id in snap_shot.concept_ids() {
+        if let Some(concept) = snap_shot.get_concept(id) {
+            text += &insert_record(
+                id,
+                snap_shot.has_variable(&no_changes, id),
+                concept,
+            );
+        }
+    }
+    text + "end\n"
+}
+
+/// Makes the changes written in `text` to `snap_shot`, checking that the first line is `header`. If `allow_unfinished` is true then a commit without an `end` line at the end of the text, as left by a crash while writing it, is ignored.
+pub fn replay(
+    snap_shot: &mut SnapShot,
+    text: &str,
+    header: &str,
+    allow_unfinished: bool,
+) -> Result<()> {
+    let mut lines = text.lines().enumerate();
+    match lines.next() {
+        Some((_, first)) if first == header => (),
+        _ => return Err(invalid(1, &format!("expected \"{}\"", header))),
+    }
+    let mut commit: Option<ContextDelta> = None;
+    for (index, line) in lines {
+        let number = index + 1;
+        let (keyword, rest) = match line.find(' ') {
+            Some(space) => (&line[..space], &line[space + 1..]),
+            None => (line, ""),
+        };
+        match (keyword, commit.take()) {
+            ("commit", None) => commit = Some(ContextDelta::default()),
+            ("compact", None) => snap_shot.compact(),
+            ("end", Some(delta)) => snap_shot.apply(delta),
+            (_, Some(mut delta)) => {
+                read_record(&mut delta, keyword, rest)
+                    .ok_or_else(|| invalid(number, "malformed change"))?;
+                commit = Some(delta);
+            },
+            (_, None) => return Err(invalid(number, "expected a commit")),
+        }
+    }
+    if commit.is_some() && !allow_unfinished {
+        Err(invalid(text.lines().count(), "unfinished commit"))
+    } else {
+        Ok(())
+    }
+}
+
+fn read_record(
+    delta: &mut ContextDelta,
+    keyword: &str,
+    rest: &str,
+) -> Option<()> {
+    match keyword {
+        "insert" => {
+            let mut fields = rest.splitn(3, ' ');
+            let id = fields.next()?.parse().ok()?;
+            let variable = match fields.next()? {
+                "0" => false,
+                "1" => true,
+                _ => return None,
+            };
+            let concept = Concept::from_record(fields.next()?)?;
+            delta
+                .concept
+                .insert(id, (ConceptDelta::Insert(concept), variable, false));
+        },
+        "update" => {
+            let mut fields = rest.splitn(2, ' ');
+            let id = fields.next()?.parse().ok()?;
+            let changes = CD::from_record(fields.next()?)?;
+            delta
+                .concept
+                .insert(id, (ConceptDelta::Update(changes), false, false));
+        },
+        "remove" => {
+            let id = rest.parse().ok()?;
+            delta.concept.insert(
+                id,
+                (ConceptDelta::Remove(Concept::default()), true, false),
+            );
+        },
+        "string" => {
+            let mut fields = rest.splitn(2, ' ');
+            let id = fields.next()?.parse().ok()?;
+            delta
+                .string
+                .insert(fields.next()?.to_string(), StringDelta::Insert(id));
+        },
+        "unstring" => {
+            delta.string.insert(rest.to_string(), StringDelta::Remove(0));
+        },
+        _ => return None,
+    }
+    Some(())
+}
+
+fn invalid(line: usize, message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("line {}: {}", line, message))
+}
@@ -40,9 +40,26 @@
 //! in parentheses will be alleviated by functionality to set the relative precedence and associativity
 //! of concepts.
 //!
-//! So far there are 10 built-in concepts. A new `Context` labels these with the symbols, `"label_of"`,
-//! `"->"`, `":="`, `"let"`, `"true"`, `"false"`, `"assoc"`, `"right"`, `"left"`, "prec", "deafult", ">" but the labels
-//! can be changed to different symbols for different languages or disciplines.
+//! So far there are 20 built-in concepts. A new `Context` labels these with the symbols, `"label_of"`,
+//! `"->"`, `":="`, `"let"`, `"true"`, `"false"`, `"assoc"`, `"right"`, `"left"`, `"prec"`, `"default"`,
+//! `">"`, `"step"`, `"steps"`, `"expand"`, `"definition_of"`, `"reduction_of"`, `"reduces_from"`,
+//! `"forget"` and `"assuming"` but the labels can be changed to different symbols for different
+//! languages or disciplines.
+//!
+//! The built-in concepts for inspecting and changing the `Context` are used as follows:
+//!
+//! - `step x` reduces `x` by a single step and `steps n x` reduces it by at most `n` steps, so that
+//!   a chain of reduction rules can be followed one rule at a time. `n` must be a non-negative
+//!   integer.
+//! - `expand x` replaces each concept in `x` with the concepts it is defined as, until only
+//!   undefined concepts are left.
+//! - `definition_of x` gives the pair of concepts that `x` is defined as, `reduction_of x` gives the
+//!   concept that `x` directly reduces to and `reduces_from x` lists the concepts that directly
+//!   reduce to `x`, separated by commas.
+//! - `forget x` removes the concept `x` along with its label and rules if no other concept is
+//!   composed of it or reduces to it.
+//! - `assuming (rule) expression` evaluates `expression` as if `let rule` had been executed, without
+//!   keeping the rule.
 //!
 //! # Examples
 //!
@@ -83,6 +100,15 @@
 //! // You can reduce a labelled concept
 //! assert_eq!(context.execute("let a -> d"), "");
 //!
+//! // Expand a concept into the symbols it is composed of
+//! assert_eq!(context.execute("let j := h i"), "");
+//! assert_eq!(context.execute("expand j"), "h i");
+//!
+//! // Inspect the relationships of a concept
+//! assert_eq!(context.execute("definition_of j"), "h i");
+//! assert_eq!(context.execute("reduction_of a"), "d");
+//! assert_eq!(context.execute("reduces_from d"), "a");
+//!
 //! // Try to specify the composition of a concept in terms of itself
 //! assert_eq!(context.execute("let b := a b"), ZiaError::InfiniteDefinition.to_string());
 //!
@@ -107,6 +133,14 @@
 //! assert_eq!(context.execute("let g"), "");
 //! assert_eq!(context.execute("g"), "true");
 //!
+//! // Forget a concept that no other concept depends on
+//! assert_eq!(context.execute("forget g"), "");
+//! assert_eq!(context.execute("g"), "g");
+//!
+//! // Evaluate an expression as if a rule had been added, without keeping the rule
+//! assert_eq!(context.execute("assuming (g -> d) (g ->)"), "d");
+//! assert_eq!(context.execute("g"), "g");
+//!
 //! // Let an arbitary expression be true
 //! assert_eq!(context.execute("let h i j"), "");
 //! assert_eq!(context.execute("h i j"), "true");
@@ -114,11 +148,17 @@
 //! // Determine associativity of symbol
 //! assert_eq!(context.execute("assoc a"), "right");
 //!
+//! // Reduce an expression by a limited number of steps
+//! assert_eq!(context.execute("let d -> e"), "");
+//! assert_eq!(context.execute("step a"), "d");
+//! assert_eq!(context.execute("steps 2 a"), "e");
+//!
 //! // Define patterns
 //! assert_eq!(context.execute("let _x_ and false -> false"), "");
 //! assert_eq!(context.execute("foo and false"), "false");
 //! ```
 
+extern crate im;
 #[macro_use]
 extern crate lazy_static;
 #[macro_use]
@@ -133,6 +173,12 @@ extern crate snafu;
 /// Abstract syntax tree. Relates syntax to concepts.
 mod ast;
 
+/// A description of the changes committed to a context.
+mod change_summary;
+
+/// A description of a concept and how it relates to other concepts.
+mod concept_details;
+
 /// The units that make up the context. Defined in terms of their relationship with other concepts.
 mod concepts;
 
@@ -152,13 +198,35 @@ mod delta;
 /// The errors that the users could make when making commands.
 mod errors;
 
+/// Writing committed changes to files and reading them back.
+mod journal;
+
+/// Combining the changes made to a common ancestor of two contexts.
+mod merge;
+
+/// The differences between the rules of two contexts.
+mod semantic_diff;
+
 mod snap_shot;
 
+/// Reading Zia commands as they are typed, such as in a shell or an editor.
+pub mod syntax;
+
+pub use change_summary::ChangeSummary;
+
+pub use concept_details::{ConceptDetails, ConceptKind};
+
 /// A container for adding, writing, reading and removing `Concept`s.
 pub use context::Context;
 
+pub use context::is_variable;
+
 pub use errors::ZiaError;
 
+pub use merge::MergeConflict;
+
+pub use semantic_diff::SemanticDiff;
+
 // Saves having to construct a new `Context` each time.
 #[macro_export]
 lazy_static! {
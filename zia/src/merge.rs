@@ -0,0 +1,56 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use concepts::Concept;
+use errors::ZiaError;
+
+/// A change that could not be merged because it disagrees with the other side. `None` means that side has no such rule or label.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeConflict {
+    /// When both sides gave the same syntax different reductions.
+    Reduction {
+        syntax: String,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    /// When both sides used the same label for concepts with different definitions, or gave the concept with that label different definitions.
+    Label {
+        label: String,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    /// When both sides gave the same concept different labels. `concept` is the syntax of the concept in the common ancestor, or of its definition if it is new to both sides.
+    Relabel {
+        concept: String,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    /// When a change from their side cannot be made to our side, such as when it would complete a cycle of chained reduction rules.
+    Rejected {
+        command: String,
+        error: ZiaError,
+    },
+}
+
+/// Whether a concept stored at the same index before and after some changes is still the same concept, rather than one stored in the place of a removed concept. A concept keeps its identity when it is relabelled or when it gains or loses a definition.
+pub fn is_same_concept(before: &Concept, after: &Concept) -> bool {
+    before.is_concrete() == after.is_concrete()
+        && before.get_string() == after.get_string()
+        && match (before.get_definition(), after.get_definition()) {
+            (Some(before), Some(after)) => before == after,
+            _ => true,
+        }
+}
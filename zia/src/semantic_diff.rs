@@ -0,0 +1,62 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+/// The rules that differ between two contexts, written as Zia commands. Rules are matched by the syntax of the concepts they are about rather than by the concepts themselves, so contexts built up in a different order can be compared.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SemanticDiff {
+    /// Commands for the rules that are only in the other context.
+    pub added: Vec<String>,
+    /// Commands that remove the rules that are only in this context.
+    pub removed: Vec<String>,
+    /// Commands for the rules of the other context that are about the same syntax as rules of this context but differ from them.
+    pub changed: Vec<String>,
+}
+
+impl SemanticDiff {
+    /// Returns true if both contexts have the same rules.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+/// Adds the differences between rules relating the syntax of each key to the syntax of its value.
+pub fn compare_rules<F, G>(
+    diff: &mut SemanticDiff,
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+    rule: F,
+    removal: G,
+) where
+    F: Fn(&str, &str) -> String,
+    G: Fn(&str) -> String,
+{
+    for (syntax, old) in before {
+        match after.get(syntax) {
+            None => diff.removed.push(removal(syntax)),
+            Some(new) if new != old => diff.changed.push(rule(syntax, new)),
+            Some(_) => (),
+        }
+    }
+    for (syntax, new) in after {
+        if !before.contains_key(syntax) {
+            diff.added.push(rule(syntax, new));
+        }
+    }
+}
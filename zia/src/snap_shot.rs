@@ -23,23 +23,34 @@ use context_delta::{ConceptDelta, ContextDelta, StringDelta};
 use context_search::ContextSearch;
 use delta::Apply;
 use errors::{ZiaError, ZiaResult};
+use im;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     rc::Rc,
 };
 
-/// A container for adding, reading, writing and removing concepts of generic type `T`.
+/// A container for adding, reading, writing and removing concepts of generic type `T`. Its collections share their unchanged parts with those of its clones, so cloning is cheap and a clone only takes up memory for what is changed in it.
 #[derive(Default, Debug, Clone)]
 pub struct SnapShot {
     /// Relates a String value to the index where the concept corresponding to the String is stored
     /// in the `concepts` field.
-    string_map: HashMap<String, usize>,
+    string_map: im::HashMap<String, usize>,
     /// Concepts may be stored at an index of this vector as `Some(T)`. If that concept is removed
     /// from the context, `None` will be left at its index.
-    concepts: Vec<Option<Concept>>,
+    concepts: im::Vector<Option<Concept>>,
     /// Keeps track of indices of the `concepts` field that have `None`.
-    gaps: Vec<usize>,
-    variables: HashSet<usize>,
+    gaps: im::Vector<usize>,
+    variables: im::HashSet<usize>,
+}
+
+/// Snapshots are equal if they have the same strings, concepts and variables, however their gaps were left.
+impl PartialEq for SnapShot {
+    fn eq(&self, other: &Self) -> bool {
+        self.string_map == other.string_map
+            && self.variables == other.variables
+            && (0..self.concepts.len().max(other.concepts.len()))
+                .all(|id| self.get_concept(id) == other.get_concept(id))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -61,6 +72,171 @@ impl SnapShot {
         )
     }
 
+    /// Returns the indices of all the concepts stored.
+    pub fn concept_ids(&self) -> Vec<usize> {
+        self.concepts
+            .iter()
+            .enumerate()
+            .filter_map(|(id, c)| c.as_ref().map(|_| id))
+            .collect()
+    }
+
+    /// Returns each string value together with the index of its string concept.
+    pub fn string_concepts(&self) -> Vec<(String, usize)> {
+        self.string_map.iter().map(|(s, id)| (s.clone(), *id)).collect()
+    }
+
+    /// Moves concepts so that there are no gaps between their indices.
+    pub fn compact(&mut self) {
+        let mapping: HashMap<usize, usize> = self
+            .concept_ids()
+            .into_iter()
+            .enumerate()
+            .map(|(new_id, old_id)| (old_id, new_id))
+            .collect();
+        self.concepts = self
+            .concepts
+            .iter()
+            .filter_map(|c| {
+                c.clone().map(|mut concept| {
+                    concept.remap(&mapping);
+                    Some(concept)
+                })
+            })
+            .collect();
+        self.string_map.iter_mut().for_each(|(_, id)| *id = mapping[id]);
+        self.variables = self.variables.iter().map(|v| mapping[v]).collect();
+        self.gaps.clear();
+    }
+
+    /// Describes each way that the stored concepts, strings, variables and gaps are inconsistent with each other.
+    pub fn check_integrity(&self) -> Vec<String> {
+        let mut violations = Vec::<String>::new();
+        let mut definitions = HashMap::<(usize, usize), usize>::new();
+        for id in self.concept_ids() {
+            let concept = self.concepts[id].as_ref().unwrap();
+            if let Some((left, right)) = concept.get_definition() {
+                if let Some(other) = definitions.insert((left, right), id) {
+                    violations.push(format!(
+                        "Concepts {} and {} have the same definition",
+                        other, id
+                    ));
+                }
+                // The labels of variables are deliberately hidden from the lefthand of the label concept.
+                let hidden_label =
+                    left == LABEL && self.variables.contains(&id);
+                match self.get_concept(left) {
+                    Some(l) if !hidden_label && !l.get_lefthand_of().contains(&id) => {
+                        violations.push(format!(
+                            "Concept {} is defined with concept {} on the left but is not in its lefthand_of",
+                            id, left
+                        ))
+                    },
+                    None => violations.push(format!(
+                        "Concept {} is defined with missing concept {}",
+                        id, left
+                    )),
+                    _ => (),
+                }
+                match self.get_concept(right) {
+                    Some(r) if !r.get_righthand_of().contains(&id) => {
+                        violations.push(format!(
+                            "Concept {} is defined with concept {} on the right but is not in its righthand_of",
+                            id, right
+                        ))
+                    },
+                    None => violations.push(format!(
+                        "Concept {} is defined with missing concept {}",
+                        id, right
+                    )),
+                    _ => (),
+                }
+            }
+            if let Some(reduction) = concept.get_reduction() {
+                match self.get_concept(reduction) {
+                    Some(r) if r.find_what_reduces_to_it().all(|c| *c != id) => {
+                        violations.push(format!(
+                            "Concept {} reduces to concept {} but is not in its reduces_from",
+                            id, reduction
+                        ))
+                    },
+                    None => violations.push(format!(
+                        "Concept {} reduces to missing concept {}",
+                        id, reduction
+                    )),
+                    _ => (),
+                }
+            }
+            for other in concept.get_lefthand_of() {
+                if self
+                    .get_concept(*other)
+                    .and_then(Concept::get_definition)
+                    .map(|(left, _)| left)
+                    != Some(id)
+                {
+                    violations.push(format!(
+                        "Concept {} is in the lefthand_of of concept {} but is not the lefthand of its definition",
+                        other, id
+                    ));
+                }
+            }
+            for other in concept.get_righthand_of() {
+                if self
+                    .get_concept(*other)
+                    .and_then(Concept::get_definition)
+                    .map(|(_, right)| right)
+                    != Some(id)
+                {
+                    violations.push(format!(
+                        "Concept {} is in the righthand_of of concept {} but is not the righthand of its definition",
+                        other, id
+                    ));
+                }
+            }
+            for other in concept.find_what_reduces_to_it() {
+                if self.get_concept(*other).and_then(Concept::get_reduction)
+                    != Some(id)
+                {
+                    violations.push(format!(
+                        "Concept {} is in the reduces_from of concept {} but does not reduce to it",
+                        other, id
+                    ));
+                }
+            }
+            if let Some(s) = concept.get_string() {
+                if self.string_map.get(&s) != Some(&id) {
+                    violations.push(format!(
+                        "String concept {} of \"{}\" is not in the string map",
+                        id, s
+                    ));
+                }
+            }
+        }
+        for (s, id) in &self.string_map {
+            if self.get_concept(*id).and_then(Concept::get_string).as_ref()
+                != Some(s)
+            {
+                violations.push(format!(
+                    "String \"{}\" is mapped to concept {} which is not its string concept",
+                    s, id
+                ));
+            }
+        }
+        for variable in &self.variables {
+            if self.get_concept(*variable).is_none() {
+                violations
+                    .push(format!("Variable {} is not a concept", variable));
+            }
+        }
+        for gap in &self.gaps {
+            if self.get_concept(*gap).is_some() {
+                violations
+                    .push(format!("Gap {} is occupied by a concept", gap));
+            }
+        }
+        violations
+    }
+
     pub fn get_concept(&self, id: usize) -> Option<&Concept> {
         match self.concepts.get(id) {
             Some(Some(c)) => Some(c),
@@ -69,33 +245,36 @@ impl SnapShot {
     }
 
     fn write_concept(&mut self, id: usize) -> &mut Concept {
-        match self.concepts[id] {
-            Some(ref mut c) => c,
-            None => panic!("No concept with id = {}", id),
+        match self.concepts.get_mut(id) {
+            Some(Some(c)) => c,
+            _ => panic!("No concept with id = {}", id),
         }
     }
 
-    pub fn read_concept(&self, delta: &ContextDelta, id: usize) -> Concept {
-        delta
-            .concept
-            .get(&id)
-            .and_then(|(cd, _, _)| match cd {
-                ConceptDelta::Insert(c) => Some(c.clone()),
-                ConceptDelta::Remove(_) => None,
-                ConceptDelta::Update(d) => {
-                    let mut concept = self
-                        .get_concept(id)
-                        .expect("Deltas imply that a concept that doesn't exist will be updated!")
-                        .clone();
-                    concept.apply(d.clone());
-                    Some(concept)
-                }
-            })
-            .unwrap_or_else(|| {
-                self.get_concept(id)
-                    .unwrap_or_else(|| panic!("No concept with id = {}", id))
-                    .clone()
-            })
+    pub fn read_concept(
+        &self,
+        delta: &ContextDelta,
+        id: usize,
+    ) -> ZiaResult<Concept> {
+        match delta.concept.get(&id) {
+            Some((ConceptDelta::Insert(c), _, _)) => Ok(c.clone()),
+            Some((ConceptDelta::Update(d), _, _)) => {
+                let mut concept = self
+                    .get_concept(id)
+                    .ok_or_else(|| ZiaError::Internal {
+                        message: "Deltas imply that a concept that doesn't exist will be updated!".to_string(),
+                    })?
+                    .clone();
+                concept.apply(d.clone());
+                Ok(concept)
+            },
+            Some((ConceptDelta::Remove(_), _, _)) | None => self
+                .get_concept(id)
+                .cloned()
+                .ok_or_else(|| ZiaError::Internal {
+                    message: format!("No concept with id = {}", id),
+                }),
+        }
     }
 
     pub fn add_concept_delta(
@@ -186,67 +365,76 @@ impl SnapShot {
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> Option<usize> {
-        self.read_concept(deltas, concept)
-            .get_reduction()
-            .map(|n| self.get_normal_form(deltas, n).unwrap_or(n))
+    ) -> ZiaResult<Option<usize>> {
+        match self.read_concept(deltas, concept)?.get_reduction() {
+            Some(n) => Ok(Some(self.get_normal_form(deltas, n)?.unwrap_or(n))),
+            None => Ok(None),
+        }
     }
 
     pub fn get_concept_of_label(
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> Option<usize> {
-        self.read_concept(deltas, concept)
-            .get_righthand_of()
-            .iter()
-            .filter(|candidate| {
-                self.read_concept(deltas, **candidate)
-                    .get_definition()
-                    .expect("Candidate should have a definition!")
-                    .0
-                    == LABEL
-            })
-            .nth(0)
-            .cloned()
+    ) -> ZiaResult<Option<usize>> {
+        for candidate in self.read_concept(deltas, concept)?.get_righthand_of()
+        {
+            let (left, _) = self
+                .read_concept(deltas, *candidate)?
+                .get_definition()
+                .ok_or_else(|| ZiaError::Internal {
+                    message: "Candidate should have a definition!".to_string(),
+                })?;
+            if left == LABEL {
+                return Ok(Some(*candidate));
+            }
+        }
+        Ok(None)
     }
 
     pub fn is_disconnected(
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> bool {
-        self.read_concept(deltas, concept).get_reduction().is_none()
-            && self.read_concept(deltas, concept).get_definition().is_none()
-            && self.read_concept(deltas, concept).get_lefthand_of().is_empty()
-            && self.righthand_of_without_label_is_empty(deltas, concept)
-            && self
-                .read_concept(deltas, concept)
-                .find_what_reduces_to_it()
-                .next()
-                .is_none()
+    ) -> ZiaResult<bool> {
+        let c = self.read_concept(deltas, concept)?;
+        Ok(c.get_reduction().is_none()
+            && c.get_definition().is_none()
+            && self.is_unused(deltas, concept)?)
+    }
+
+    /// Whether no other concept is composed of, or reduces to, the concept, ignoring the concept that labels it.
+    pub fn is_unused(
+        &self,
+        deltas: &ContextDelta,
+        concept: usize,
+    ) -> ZiaResult<bool> {
+        let c = self.read_concept(deltas, concept)?;
+        Ok(c.get_lefthand_of().is_empty()
+            && self.righthand_of_without_label_is_empty(deltas, concept)?
+            && c.find_what_reduces_to_it().next().is_none())
     }
 
     fn righthand_of_without_label_is_empty(
         &self,
         deltas: &ContextDelta,
         con: usize,
-    ) -> bool {
-        self.read_concept(deltas, con)
-            .get_righthand_of()
-            .iter()
-            .filter_map(|concept| {
-                self.read_concept(deltas, *concept)
-                    .get_definition()
-                    .filter(|(left, _)| *left != LABEL)
-            })
-            .nth(0)
-            .is_none()
+    ) -> ZiaResult<bool> {
+        for concept in self.read_concept(deltas, con)?.get_righthand_of() {
+            if let Some((left, _)) =
+                self.read_concept(deltas, *concept)?.get_definition()
+            {
+                if left != LABEL {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
     }
 
     fn blindly_remove_concept(&mut self, id: usize) {
-        self.concepts[id] = None;
-        self.gaps.push(id);
+        self.concepts.set(id, None);
+        self.gaps.push_back(id);
     }
 
     fn remove_string(&mut self, string: &str) {
@@ -280,7 +468,7 @@ impl SnapShot {
                     return Err(ZiaError::AmbiguousExpression);
                 }
                 let assoc = lp_syntax.iter().try_fold(None, |assoc, syntax| {
-                    match (self.get_associativity(delta, syntax), assoc) {
+                    match (self.get_associativity(delta, syntax)?, assoc) {
                         (Some(x), Some(y)) => {
                             if x == y {
                                 Ok(Some(x))
@@ -315,7 +503,7 @@ impl SnapShot {
                                 delta,
                                 &tokens[..lp_indices[0]],
                             )?;
-                            Ok(self.combine(delta, &head, &tail))
+                            self.combine(delta, &head, &tail)
                         }
                     },
                     Some(Associativity::Left) => lp_indices
@@ -381,7 +569,7 @@ impl SnapShot {
                             )?
                         },
                         &edge_syntax,
-                    ),
+                    )?,
                     Associativity::Right => self.combine(
                         delta,
                         &edge_syntax,
@@ -390,7 +578,7 @@ impl SnapShot {
                         } else {
                             self.ast_from_tokens(delta, &slice[1..])?
                         },
-                    ),
+                    )?,
                 }
             }
         } else {
@@ -402,10 +590,10 @@ impl SnapShot {
                 None => lp_with_the_rest,
                 Some(e) => match assoc {
                     Associativity::Left => {
-                        self.combine(delta, &e, &lp_with_the_rest)
+                        self.combine(delta, &e, &lp_with_the_rest)?
                     },
                     Associativity::Right => {
-                        self.combine(delta, &lp_with_the_rest, &e)
+                        self.combine(delta, &lp_with_the_rest, &e)?
                     },
                 },
             }),
@@ -419,8 +607,8 @@ impl SnapShot {
         delta: &ContextDelta,
         tokens: &[String],
     ) -> ZiaResult<TokenSubsequence> {
-        let precedence_syntax = self.to_ast(delta, PRECEDENCE);
-        let greater_than_syntax = self.to_ast(delta, GREATER_THAN);
+        let precedence_syntax = self.to_ast(delta, PRECEDENCE)?;
+        let greater_than_syntax = self.to_ast(delta, GREATER_THAN)?;
         let (syntax, positions, _number_of_tokens) = tokens.iter().try_fold(
             // Initially assume no concepts have the lowest precedence
             (Vec::<Rc<SyntaxTree>>::new(), Vec::<usize>::new(), None),
@@ -430,11 +618,11 @@ impl SnapShot {
                 let this_index = prev_index.map(|x| x + 1).or(Some(0));
                 let syntax_of_token = self.ast_from_token(delta, token)?;
                 let precedence_of_token =
-                    self.combine(delta, &precedence_syntax, &syntax_of_token);
+                    self.combine(delta, &precedence_syntax, &syntax_of_token)?;
                 // Compare current token's precedence with each currently assumed lowest syntax
                 for syntax in lowest_precedence_syntax.clone() {
                     let precedence_of_syntax =
-                        self.combine(delta, &precedence_syntax, &syntax);
+                        self.combine(delta, &precedence_syntax, &syntax)?;
                     let comparing_between_tokens = self.combine(
                         delta,
                         &precedence_of_syntax,
@@ -442,10 +630,10 @@ impl SnapShot {
                             delta,
                             &greater_than_syntax,
                             &precedence_of_token,
-                        ),
-                    );
+                        )?,
+                    )?;
                     match ContextSearch::from((self, delta))
-                        .recursively_reduce(&comparing_between_tokens)
+                        .recursively_reduce(&comparing_between_tokens)?
                         .get_concept()
                     {
                         // syntax of token has an even lower precedence than some previous lowest precendence syntax
@@ -475,12 +663,12 @@ impl SnapShot {
                                         delta,
                                         &greater_than_syntax,
                                         &precedence_of_syntax,
-                                    ),
-                                );
+                                    )?,
+                                )?;
                             match ContextSearch::from((self, delta))
                                 .recursively_reduce(
                                     &comparing_between_tokens_reversed,
-                                )
+                                )?
                                 .get_concept()
                             {
                                 // syntax of token has an even lower precedence than some previous lowest precendence syntax
@@ -528,7 +716,7 @@ impl SnapShot {
     ) -> ZiaResult<Rc<SyntaxTree>> {
         let lefthand = self.ast_from_token(deltas, left)?;
         let righthand = self.ast_from_token(deltas, right)?;
-        Ok(self.combine(deltas, &lefthand, &righthand))
+        self.combine(deltas, &lefthand, &righthand)
     }
 
     fn ast_from_token(
@@ -539,7 +727,25 @@ impl SnapShot {
         if t.contains(' ') || t.contains('(') || t.contains(')') {
             self.ast_from_expression(deltas, t)
         } else {
-            Ok(Rc::new(self.ast_from_symbol(deltas, t)))
+            Ok(Rc::new(self.ast_from_symbol(deltas, t)?))
+        }
+    }
+
+    /// Returns the syntax tree with its symbols and expressions bound to the concepts they now represent, such as those added since the syntax tree was made.
+    pub fn bind_concepts(
+        &self,
+        deltas: &ContextDelta,
+        ast: &Rc<SyntaxTree>,
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        match ast.get_expansion() {
+            None => {
+                Ok(Rc::new(self.ast_from_symbol(deltas, &ast.to_string())?))
+            },
+            Some((left, right)) => self.combine(
+                deltas,
+                &self.bind_concepts(deltas, &left)?,
+                &self.bind_concepts(deltas, &right)?,
+            ),
         }
     }
 
@@ -547,19 +753,25 @@ impl SnapShot {
         &self,
         deltas: &ContextDelta,
         s: &str,
-    ) -> Option<usize> {
-        self.get_string_concept(deltas, s)
-            .and_then(|c| self.get_labellee(deltas, c))
+    ) -> ZiaResult<Option<usize>> {
+        match self.get_string_concept(deltas, s) {
+            Some(c) => self.get_labellee(deltas, c),
+            None => Ok(None),
+        }
     }
 
-    fn ast_from_symbol(&self, deltas: &ContextDelta, s: &str) -> SyntaxTree {
-        self.concept_from_label(deltas, s).map_or_else(
+    fn ast_from_symbol(
+        &self,
+        deltas: &ContextDelta,
+        s: &str,
+    ) -> ZiaResult<SyntaxTree> {
+        Ok(self.concept_from_label(deltas, s)?.map_or_else(
             || s.parse().unwrap(),
             |concept| s.parse::<SyntaxTree>().unwrap().bind_concept(concept),
-        )
+        ))
     }
 
-    fn get_string_concept(
+    pub fn get_string_concept(
         &self,
         delta: &ContextDelta,
         s: &str,
@@ -586,16 +798,16 @@ impl SnapShot {
         deltas: &ContextDelta,
         outer: usize,
         inner: usize,
-    ) -> bool {
+    ) -> ZiaResult<bool> {
         if let Some((left, right)) =
-            self.read_concept(deltas, outer).get_definition()
+            self.read_concept(deltas, outer)?.get_definition()
         {
-            left == inner
+            Ok(left == inner
                 || right == inner
-                || self.contains(deltas, left, inner)
-                || self.contains(deltas, right, inner)
+                || self.contains(deltas, left, inner)?
+                || self.contains(deltas, right, inner)?)
         } else {
-            false
+            Ok(false)
         }
     }
 
@@ -604,36 +816,41 @@ impl SnapShot {
         delta: &ContextDelta,
         lefthand: usize,
         righthand: usize,
-    ) -> Option<usize> {
-        let lc = self.read_concept(delta, lefthand);
-        let rc = self.read_concept(delta, righthand);
+    ) -> ZiaResult<Option<usize>> {
+        let lc = self.read_concept(delta, lefthand)?;
+        let rc = self.read_concept(delta, righthand)?;
         let has_lefthand = lc.get_lefthand_of();
         let has_righthand = rc.get_righthand_of();
         let mut candidates = has_lefthand.intersection(has_righthand);
-        candidates.next().map(|index| {
-            candidates.next().map_or(*index, |_| {
-                panic!("Multiple definitions with the same lefthand and righthand pair exist.")
-            })
-        })
+        match (candidates.next(), candidates.next()) {
+            (index, None) => Ok(index.cloned()),
+            _ => Err(ZiaError::Internal {
+                message: "Multiple definitions with the same lefthand and righthand pair exist.".to_string(),
+            }),
+        }
     }
 
-    fn get_labellee(&self, delta: &ContextDelta, c: usize) -> Option<usize> {
-        let concept = self.read_concept(delta, c);
+    fn get_labellee(
+        &self,
+        delta: &ContextDelta,
+        c: usize,
+    ) -> ZiaResult<Option<usize>> {
+        let concept = self.read_concept(delta, c)?;
         let mut candidates: VecDeque<usize> =
             concept.find_what_reduces_to_it().copied().collect();
         loop {
             if let Some(candidate) = candidates.pop_front() {
-                let candidate_concept = self.read_concept(delta, candidate);
+                let candidate_concept = self.read_concept(delta, candidate)?;
                 if let Some((r, x)) = candidate_concept.get_definition() {
                     if r == LABEL {
-                        return Some(x);
+                        return Ok(Some(x));
                     }
                 }
                 let extra_candidates =
                     candidate_concept.find_what_reduces_to_it().copied();
                 candidates.extend(extra_candidates);
             } else {
-                return None;
+                return Ok(None);
             }
         }
     }
@@ -642,15 +859,16 @@ impl SnapShot {
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> Option<String> {
-        match self.get_concept_of_label(deltas, concept) {
-            None => self
-                .read_concept(deltas, concept)
-                .get_reduction()
-                .and_then(|r| self.get_label(deltas, r)),
-            Some(d) => self
-                .get_normal_form(deltas, d)
-                .and_then(|n| self.read_concept(deltas, n).get_string()),
+    ) -> ZiaResult<Option<String>> {
+        match self.get_concept_of_label(deltas, concept)? {
+            None => match self.read_concept(deltas, concept)?.get_reduction() {
+                Some(r) => self.get_label(deltas, r),
+                None => Ok(None),
+            },
+            Some(d) => match self.get_normal_form(deltas, d)? {
+                Some(n) => Ok(self.read_concept(deltas, n)?.get_string()),
+                None => Ok(None),
+            },
         }
     }
 
@@ -659,15 +877,15 @@ impl SnapShot {
         &self,
         deltas: &ContextDelta,
         ast: &Rc<SyntaxTree>,
-    ) -> Rc<SyntaxTree> {
+    ) -> ZiaResult<Rc<SyntaxTree>> {
         if let Some(con) = ast.get_concept() {
             if let Some((left, right)) =
-                self.read_concept(deltas, con).get_definition()
+                self.read_concept(deltas, con)?.get_definition()
             {
                 self.combine(
                     deltas,
-                    &self.expand(deltas, &self.to_ast(deltas, left)),
-                    &self.expand(deltas, &self.to_ast(deltas, right)),
+                    &self.expand(deltas, &self.to_ast(deltas, left)?)?,
+                    &self.expand(deltas, &self.to_ast(deltas, right)?)?,
                 )
             } else {
                 self.to_ast(deltas, con)
@@ -675,11 +893,11 @@ impl SnapShot {
         } else if let Some((ref left, ref right)) = ast.get_expansion() {
             self.combine(
                 deltas,
-                &self.expand(deltas, left),
-                &self.expand(deltas, right),
+                &self.expand(deltas, left)?,
+                &self.expand(deltas, right)?,
             )
         } else {
-            ast.clone()
+            Ok(ast.clone())
         }
     }
 
@@ -688,44 +906,80 @@ impl SnapShot {
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> Rc<SyntaxTree> {
-        if let Some(s) = self.get_label(deltas, concept) {
-            Rc::new(s.parse::<SyntaxTree>().unwrap().bind_concept(concept))
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        if let Some(s) = self.get_label(deltas, concept)? {
+            Ok(Rc::new(s.parse::<SyntaxTree>().unwrap().bind_concept(concept)))
         } else {
             let (left, right) = self
-                .read_concept(deltas, concept)
+                .read_concept(deltas, concept)?
                 .get_definition()
-                .unwrap_or_else(|| {
-                    panic!(
+                .ok_or_else(|| ZiaError::Internal {
+                    message: format!(
                         "Unlabelled concept ({}) with no definition",
                         concept
-                    )
-                });
+                    ),
+                })?;
             self.combine(
                 deltas,
-                &self.to_ast(deltas, left),
-                &self.to_ast(deltas, right),
+                &self.to_ast(deltas, left)?,
+                &self.to_ast(deltas, right)?,
             )
         }
     }
 
+    /// Returns the syntax for a concept without borrowing the labels of the concepts it reduces to.
+    pub fn to_unreduced_ast(
+        &self,
+        deltas: &ContextDelta,
+        concept: usize,
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        match (
+            self.get_concept_of_label(deltas, concept)?,
+            self.read_concept(deltas, concept)?.get_definition(),
+        ) {
+            (None, Some((left, right))) => self.combine(
+                deltas,
+                &self.to_unreduced_ast(deltas, left)?,
+                &self.to_unreduced_ast(deltas, right)?,
+            ),
+            _ => self.to_ast(deltas, concept),
+        }
+    }
+
+    /// Rebuilds syntax so that each part is displayed as the concept it is associated with rather than what that concept reduces to.
+    pub fn unreduce_labels(
+        &self,
+        deltas: &ContextDelta,
+        ast: &Rc<SyntaxTree>,
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        if let Some(concept) = ast.get_concept() {
+            self.to_unreduced_ast(deltas, concept)
+        } else if let Some((ref left, ref right)) = ast.get_expansion() {
+            self.combine(
+                deltas,
+                &self.unreduce_labels(deltas, left)?,
+                &self.unreduce_labels(deltas, right)?,
+            )
+        } else {
+            Ok(ast.clone())
+        }
+    }
+
     pub fn combine(
         &self,
         deltas: &ContextDelta,
         ast: &Rc<SyntaxTree>,
         other: &Rc<SyntaxTree>,
-    ) -> Rc<SyntaxTree> {
-        let syntax = ast
-            .get_concept()
-            .and_then(|l| {
-                other.get_concept().and_then(|r| {
-                    self.find_definition(deltas, l, r).map(|concept| {
-                        self.join(deltas, ast, other).bind_concept(concept)
-                    })
-                })
-            })
-            .unwrap_or_else(|| self.join(deltas, ast, other));
-        Rc::new(syntax)
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        let definition = match (ast.get_concept(), other.get_concept()) {
+            (Some(l), Some(r)) => self.find_definition(deltas, l, r)?,
+            _ => None,
+        };
+        let syntax = self.join(deltas, ast, other)?;
+        Ok(Rc::new(match definition {
+            Some(concept) => syntax.bind_concept(concept),
+            None => syntax,
+        }))
     }
 
     fn join(
@@ -733,11 +987,12 @@ impl SnapShot {
         deltas: &ContextDelta,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> SyntaxTree {
-        self.display_joint(deltas, left, right)
+    ) -> ZiaResult<SyntaxTree> {
+        Ok(self
+            .display_joint(deltas, left, right)?
             .parse::<SyntaxTree>()
             .unwrap()
-            .bind_pair(left, right)
+            .bind_pair(left, right))
     }
 
     fn display_joint(
@@ -745,50 +1000,56 @@ impl SnapShot {
         deltas: &ContextDelta,
         left: &Rc<SyntaxTree>,
         right: &Rc<SyntaxTree>,
-    ) -> String {
-        let left_string = left.get_expansion().map_or_else(
-            || left.to_string(),
-            |(l, r)| match self.get_associativity(deltas, &r).unwrap() {
-                Associativity::Left => l.to_string() + " " + &r.to_string(),
-                Associativity::Right => {
-                    "(".to_string()
-                        + &l.to_string()
-                        + " "
-                        + &r.to_string()
-                        + ")"
-                },
+    ) -> ZiaResult<String> {
+        let left_string = match left.get_expansion() {
+            None => left.to_string(),
+            Some((l, r)) => {
+                match self.get_associativity(deltas, &r)?.unwrap() {
+                    Associativity::Left => l.to_string() + " " + &r.to_string(),
+                    Associativity::Right => {
+                        "(".to_string()
+                            + &l.to_string()
+                            + " "
+                            + &r.to_string()
+                            + ")"
+                    },
+                }
             },
-        );
-        let right_string = right.get_expansion().map_or_else(
-            || right.to_string(),
-            |(l, r)| match self.get_associativity(deltas, &l).unwrap() {
-                Associativity::Left => {
-                    "(".to_string()
-                        + &l.to_string()
-                        + " "
-                        + &r.to_string()
-                        + ")"
-                },
-                Associativity::Right => l.to_string() + " " + &r.to_string(),
+        };
+        let right_string = match right.get_expansion() {
+            None => right.to_string(),
+            Some((l, r)) => {
+                match self.get_associativity(deltas, &l)?.unwrap() {
+                    Associativity::Left => {
+                        "(".to_string()
+                            + &l.to_string()
+                            + " "
+                            + &r.to_string()
+                            + ")"
+                    },
+                    Associativity::Right => {
+                        l.to_string() + " " + &r.to_string()
+                    },
+                }
             },
-        );
-        left_string + " " + &right_string
+        };
+        Ok(left_string + " " + &right_string)
     }
 
     fn get_associativity(
         &self,
         deltas: &ContextDelta,
         ast: &Rc<SyntaxTree>,
-    ) -> Option<Associativity> {
+    ) -> ZiaResult<Option<Associativity>> {
         let assoc_of_ast =
-            self.combine(deltas, &self.to_ast(deltas, ASSOC), ast);
-        ContextSearch::from((self, deltas)).reduce(&assoc_of_ast).and_then(
+            self.combine(deltas, &self.to_ast(deltas, ASSOC)?, ast)?;
+        Ok(ContextSearch::from((self, deltas)).reduce(&assoc_of_ast)?.and_then(
             |ast| match ast.get_concept() {
                 Some(LEFT) => Some(Associativity::Left),
                 Some(RIGHT) => Some(Associativity::Right),
                 _ => None,
             },
-        )
+        ))
     }
 
     /// Returns the abstract syntax from two syntax parts, using the label and concept of the composition of associated concepts if it exists.
@@ -797,29 +1058,26 @@ impl SnapShot {
         deltas: &ContextDelta,
         lefthand: &Rc<SyntaxTree>,
         righthand: &Rc<SyntaxTree>,
-    ) -> Rc<SyntaxTree> {
-        Rc::new(
-            lefthand
-                .get_concept()
-                .and_then(|lc| {
-                    righthand.get_concept().and_then(|rc| {
-                        self.find_definition(deltas, lc, rc).and_then(|def| {
-                            self.get_label(deltas, def).map(|label| {
-                                label
-                                    .parse::<SyntaxTree>()
-                                    .unwrap()
-                                    .bind_concept(def)
-                            })
-                        })
-                    })
-                })
-                .unwrap_or_else(|| {
-                    self.display_joint(deltas, lefthand, righthand)
-                        .parse::<SyntaxTree>()
-                        .unwrap()
-                })
-                .bind_pair(lefthand, righthand),
-        )
+    ) -> ZiaResult<Rc<SyntaxTree>> {
+        let definition = match (lefthand.get_concept(), righthand.get_concept())
+        {
+            (Some(lc), Some(rc)) => self.find_definition(deltas, lc, rc)?,
+            _ => None,
+        };
+        let labelled_syntax = match definition {
+            Some(def) => self.get_label(deltas, def)?.map(|label| {
+                label.parse::<SyntaxTree>().unwrap().bind_concept(def)
+            }),
+            None => None,
+        };
+        let syntax = match labelled_syntax {
+            Some(syntax) => syntax,
+            None => self
+                .display_joint(deltas, lefthand, righthand)?
+                .parse::<SyntaxTree>()
+                .unwrap(),
+        };
+        Ok(Rc::new(syntax.bind_pair(lefthand, righthand)))
     }
 
     pub fn check_reductions(
@@ -829,9 +1087,9 @@ impl SnapShot {
         inner_concept: usize,
     ) -> ZiaResult<()> {
         if let Some(r) =
-            self.read_concept(deltas, inner_concept).get_reduction()
+            self.read_concept(deltas, inner_concept)?.get_reduction()
         {
-            if r == outer_concept || self.contains(deltas, r, outer_concept) {
+            if r == outer_concept || self.contains(deltas, r, outer_concept)? {
                 Err(ZiaError::InfiniteDefinition)
             } else {
                 self.check_reductions(deltas, outer_concept, r)
@@ -845,31 +1103,32 @@ impl SnapShot {
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> usize {
-        self.read_concept(deltas, concept)
-            .get_definition()
-            .and_then(|(left, right)| {
-                self.find_definition(
+    ) -> ZiaResult<usize> {
+        match self.read_concept(deltas, concept)?.get_definition() {
+            Some((left, right)) => Ok(self
+                .find_definition(
                     deltas,
                     self.get_reduction_or_reduction_of_composition(
                         deltas, left,
-                    ),
+                    )?,
                     self.get_reduction_or_reduction_of_composition(
                         deltas, right,
-                    ),
-                )
-            })
-            .unwrap_or(concept)
+                    )?,
+                )?
+                .unwrap_or(concept)),
+            None => Ok(concept),
+        }
     }
 
     fn get_reduction_or_reduction_of_composition(
         &self,
         deltas: &ContextDelta,
         concept: usize,
-    ) -> usize {
-        self.read_concept(deltas, concept).get_reduction().unwrap_or_else(
-            || self.get_reduction_of_composition(deltas, concept),
-        )
+    ) -> ZiaResult<usize> {
+        match self.read_concept(deltas, concept)?.get_reduction() {
+            Some(r) => Ok(r),
+            None => self.get_reduction_of_composition(deltas, concept),
+        }
     }
 }
 
@@ -892,14 +1151,19 @@ impl Apply for SnapShot {
                 match cd {
                     ConceptDelta::Insert(c) => {
                         if self.concepts.len() <= id {
+                            self.gaps.extend(self.concepts.len()..id);
                             self.concepts
                                 .extend(vec![None; id - self.concepts.len()]);
-                            self.concepts.push(Some(c));
+                            self.concepts.push_back(Some(c));
                         } else {
-                            self.concepts[id] = Some(c);
+                            self.concepts.set(id, Some(c));
+                            self.gaps.retain(|gap| *gap != id);
                         }
+                        // An inserted concept may replace a concept that differs in whether it is a variable
                         if v {
                             self.variables.insert(id);
+                        } else {
+                            self.variables.remove(&id);
                         }
                     },
                     ConceptDelta::Remove(_) => {
@@ -914,10 +1178,76 @@ impl Apply for SnapShot {
         }
     }
 
-    fn diff(&self, _other: Self) -> ContextDelta {
+    fn diff(&self, next: Self) -> ContextDelta {
+        let mut string = HashMap::<String, StringDelta>::new();
+        for (s, before) in &self.string_map {
+            match next.string_map.get(s) {
+                None => {
+                    string.insert(s.clone(), StringDelta::Remove(*before));
+                },
+                Some(after) if after != before => {
+                    string.insert(
+                        s.clone(),
+                        StringDelta::Update {
+                            before: *before,
+                            after: *after,
+                        },
+                    );
+                },
+                Some(_) => (),
+            }
+        }
+        for (s, after) in &next.string_map {
+            if !self.string_map.contains_key(s) {
+                string.insert(s.clone(), StringDelta::Insert(*after));
+            }
+        }
+        let mut concept = HashMap::<usize, (ConceptDelta, bool, bool)>::new();
+        for id in 0..self.concepts.len().max(next.concepts.len()) {
+            let was_variable = self.variables.contains(&id);
+            let is_variable = next.variables.contains(&id);
+            match (self.get_concept(id), next.get_concept(id)) {
+                (Some(before), None) => {
+                    concept.insert(
+                        id,
+                        (
+                            ConceptDelta::Remove(before.clone()),
+                            was_variable,
+                            false,
+                        ),
+                    );
+                },
+                (None, Some(after)) => {
+                    concept.insert(
+                        id,
+                        (
+                            ConceptDelta::Insert(after.clone()),
+                            is_variable,
+                            false,
+                        ),
+                    );
+                },
+                (Some(before), Some(after))
+                    if before != after || was_variable != is_variable =>
+                {
+                    let update = before.diff(after.clone());
+                    let mut updated = before.clone();
+                    updated.apply(update.clone());
+                    // Concepts that change kind or variable status cannot be updated so are replaced
+                    let concept_delta =
+                        if &updated == after && was_variable == is_variable {
+                            ConceptDelta::Update(update)
+                        } else {
+                            ConceptDelta::Insert(after.clone())
+                        };
+                    concept.insert(id, (concept_delta, is_variable, false));
+                },
+                _ => (),
+            }
+        }
         ContextDelta {
-            string: hashmap! {},
-            concept: hashmap! {},
+            string,
+            concept,
         }
     }
 }
@@ -992,3 +1322,67 @@ struct TokenSubsequence {
     syntax: Vec<Rc<SyntaxTree>>,
     positions: Vec<usize>,
 }
+
+#[cfg(test)]
+mod tests {
+    use delta::Apply;
+    use journal::{replay, SNAPSHOT_HEADER};
+    use snap_shot::SnapShot;
+
+    fn from_records(records: &str) -> SnapShot {
+        let mut snap_shot = SnapShot::default();
+        replay(
+            &mut snap_shot,
+            &format!("{}\ncommit\n{}end\n", SNAPSHOT_HEADER, records),
+            SNAPSHOT_HEADER,
+            false,
+        )
+        .unwrap();
+        snap_shot
+    }
+
+    fn assert_round_trip(before: &SnapShot, after: &SnapShot) {
+        let mut applied = before.clone();
+        applied.apply(before.diff(after.clone()));
+        assert_eq!(&applied, after);
+    }
+
+    #[test]
+    fn diff_round_trips() {
+        // 0 is unchanged, 1 gains a reduction, 2 is removed, 3 becomes a
+        // variable, 4 stops being one, 5 changes from a string to an abstract
+        // concept, the string "bar" moves from 6 to 8 and 9 changes from
+        // being labelled "foo" to being labelled "bar".
+        let before = from_records(
+            "string 5 foo
+string 6 bar
+insert 0 0 7 - - concrete
+insert 1 0 - - - abstract - -
+insert 2 0 - - - abstract - -
+insert 3 0 - - - abstract - -
+insert 4 1 - - - abstract - -
+insert 5 0 - - 7 string foo
+insert 6 0 - - - string bar
+insert 7 0 - - - abstract 0,9 5
+insert 9 0 - 7 - abstract - -
+",
+        );
+        let after = from_records(
+            "string 8 bar
+insert 0 0 7 - 1 concrete
+insert 1 0 - - - abstract - 0
+insert 3 1 - - - abstract - -
+insert 4 0 - - - abstract - -
+insert 5 0 - - - abstract - -
+insert 7 0 - - - abstract 0,9 8
+insert 8 0 - - 7 string bar
+insert 9 0 - 7 - abstract - -
+",
+        );
+        assert_round_trip(&before, &after);
+        assert_round_trip(&after, &before);
+        assert_round_trip(&before, &before);
+        assert_round_trip(&SnapShot::default(), &after);
+        assert_round_trip(&after, &SnapShot::default());
+    }
+}
@@ -0,0 +1,44 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+/// Whether `command` opens more parentheses than it closes, so that a shell or editor reading it line by line should continue it on the next line. Parentheses closed before they are opened are left for `Context::execute` to report.
+#[must_use]
+pub fn is_unbalanced(command: &str) -> bool {
+    let mut depth: usize = 0;
+    for c in command.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+    }
+    depth > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_unbalanced;
+
+    #[test]
+    fn unclosed_parentheses_are_unbalanced() {
+        assert!(!is_unbalanced("let (a b) -> c"));
+        assert!(is_unbalanced("let (a (b"));
+        assert!(is_unbalanced("let (a (b) c"));
+        // A stray `)` is left for `Context::execute` to report
+        assert!(!is_unbalanced("a) b"));
+        assert!(is_unbalanced("a) (b"));
+    }
+}
@@ -0,0 +1,85 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+#[macro_use]
+extern crate proptest;
+#[macro_use]
+extern crate test_zia;
+extern crate zia;
+
+// Needed for assume_abstract macro
+use test_zia::CONCRETE_SYMBOLS;
+use zia::{ZiaError, NEW_CONTEXT};
+
+proptest! {
+    #[test]
+    fn assumed_reduction_is_not_kept(a in "\\PC*", b in "\\PC*") {
+        assume_symbols!(a, b);
+        assume_abstract!(a);
+        assume_abstract!(b);
+        prop_assume!(a != b);
+        let mut cont = NEW_CONTEXT.clone();
+        prop_assert_eq!(cont.execute(&format!("assuming ({} -> {}) ({} ->)", a, b, a)), b);
+        prop_assert_eq!(cont.execute(&a), a.clone());
+        prop_assert_eq!(cont.execute(&format!("label_of {}", a)), format!("'{}'", a));
+    }
+}
+
+#[test]
+fn assumption_applies_to_existing_rules() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("assuming (c -> d) (a b)"), "d");
+    assert_eq!(cont.execute("a b"), "c");
+    assert_eq!(cont.execute("c"), "c");
+}
+
+#[test]
+fn nested_assumptions() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(
+        cont.execute("assuming (a -> b) (assuming (b -> c) (a ->))"),
+        "c"
+    );
+    assert_eq!(cont.execute("a"), "a");
+    assert_eq!(cont.execute("b"), "b");
+}
+
+#[test]
+fn assumed_definition() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("assuming (c := a b) (label_of (a b))"), "'c'");
+    assert_eq!(cont.execute("label_of (a b)"), "'a b'");
+}
+
+#[test]
+fn commands_under_assumptions_are_not_kept() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("assuming (a -> b) (let c -> a)"), "");
+    assert_eq!(cont.execute("c"), "c");
+    assert_eq!(cont.execute("a"), "a");
+}
+
+#[test]
+fn invalid_assumption() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let a -> b"), "");
+    assert_eq!(
+        cont.execute("assuming (b -> a) a"),
+        ZiaError::CyclicReduction.to_string()
+    );
+    assert_eq!(cont.execute("a"), "b");
+    assert_eq!(cont.execute("b"), "b");
+}
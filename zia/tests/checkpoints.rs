@@ -0,0 +1,106 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use std::sync::{Arc, Mutex};
+use zia::NEW_CONTEXT;
+
+#[test]
+fn restore_checkpoint() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    cont.checkpoint("before-refactor");
+    assert_eq!(cont.execute("let d := a b"), "");
+    assert_eq!(cont.execute("let (a b) -> e"), "");
+    assert_eq!(cont.execute("let f -> g"), "");
+    assert!(cont.restore("before-refactor"));
+    assert_eq!(cont.execute("a b"), "c");
+    assert_eq!(cont.execute("label_of (a b)"), "'c'");
+    assert_eq!(cont.execute("f"), "f");
+    assert_eq!(cont.check_integrity(), Vec::<String>::new());
+    assert_eq!(cont.execute("let f -> h"), "");
+    assert_eq!(cont.execute("f"), "h");
+}
+
+#[test]
+fn checkpoints_can_be_restored_again() {
+    let mut cont = NEW_CONTEXT.clone();
+    cont.checkpoint("empty");
+    assert_eq!(cont.execute("let a -> b"), "");
+    cont.checkpoint("reduced");
+    assert!(cont.restore("empty"));
+    assert_eq!(cont.execute("a"), "a");
+    assert!(cont.restore("reduced"));
+    assert_eq!(cont.execute("a"), "b");
+    assert!(cont.restore("empty"));
+    assert_eq!(cont.execute("a"), "a");
+    assert!(cont.diff_semantic(&NEW_CONTEXT).unwrap().is_empty());
+}
+
+#[test]
+fn restoring_after_garbage_collection() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let (a b) -> a b"), "");
+    cont.checkpoint("garbage");
+    assert!(cont.collect_garbage(true).unwrap() > 0);
+    assert_eq!(cont.execute("let d -> e"), "");
+    assert!(cont.restore("garbage"));
+    assert_eq!(cont.execute("d"), "d");
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("a b"), "c");
+    assert_eq!(cont.check_integrity(), Vec::<String>::new());
+}
+
+#[test]
+fn list_checkpoints() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert!(cont.checkpoints().is_empty());
+    assert!(!cont.restore("missing"));
+    cont.checkpoint("second");
+    cont.checkpoint("first");
+    cont.checkpoint("second");
+    assert_eq!(cont.checkpoints(), ["first", "second"]);
+}
+
+#[test]
+fn observers_are_told_about_restored_changes() {
+    let mut cont = NEW_CONTEXT.clone();
+    let reductions = Arc::new(Mutex::new(Vec::new()));
+    let observed = reductions.clone();
+    cont.subscribe(move |summary| {
+        observed
+            .lock()
+            .unwrap()
+            .extend(summary.reductions_changed().iter().cloned())
+    });
+    cont.checkpoint("start");
+    assert_eq!(cont.execute("let a -> b"), "");
+    assert!(cont.restore("start"));
+    assert_eq!(*reductions.lock().unwrap(), ["a", "a"]);
+}
+
+#[test]
+fn removed_checkpoint_cannot_be_restored() {
+    let mut cont = NEW_CONTEXT.clone();
+    cont.checkpoint("empty");
+    assert_eq!(cont.execute("let a -> b"), "");
+    assert!(cont.remove_checkpoint("empty"));
+    assert!(!cont.remove_checkpoint("empty"));
+    assert!(!cont.restore("empty"));
+    assert_eq!(cont.execute("a"), "b");
+    assert!(cont.checkpoints().is_empty());
+}
@@ -158,3 +158,27 @@ proptest! {
         );
     }
 }
+
+// Expanding a concept shows the symbols it is composed of without reducing them
+#[test]
+fn expand_definition() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let d := c e"), "");
+    assert_eq!(cont.execute("let a -> f"), "");
+    assert_eq!(cont.execute("expand d"), "(a b) e");
+    assert_eq!(cont.execute("expand a"), "a");
+}
+
+// Removing a definition forgets the concepts it was composed of along with their labels
+#[test]
+fn remove_definition_with_unused_parts() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let c := c"), "");
+    assert_eq!(cont.check_integrity(), Vec::<String>::new());
+    assert!(!cont.labels().contains(&"a".to_string()));
+    assert!(!cont.labels().contains(&"b".to_string()));
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("a b"), "c");
+}
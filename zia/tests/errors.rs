@@ -0,0 +1,34 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use zia::{ZiaError, NEW_CONTEXT};
+
+// `try_execute` returns the error itself rather than its message
+#[test]
+fn errors_can_be_told_apart_from_output() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.try_execute("let a -> b"), Ok(String::new()));
+    assert_eq!(cont.try_execute("a"), Ok("b".to_string()));
+    assert_eq!(
+        cont.try_execute("let a -> b"),
+        Err(ZiaError::RedundantReduction)
+    );
+    assert_eq!(
+        cont.try_execute("let a -> b").unwrap_err().variant_name(),
+        "RedundantReduction"
+    );
+}
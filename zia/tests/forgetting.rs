@@ -0,0 +1,94 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+#[macro_use]
+extern crate proptest;
+extern crate zia;
+
+use zia::{ZiaError, NEW_CONTEXT};
+
+#[test]
+fn forget_reduction() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("forget (a b)"), "");
+    assert_eq!(cont.execute("a b"), "a b");
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("a b"), "c");
+}
+
+#[test]
+fn forget_keeps_labelled_reduction() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let a -> b"), "");
+    assert_eq!(cont.execute("forget a"), "");
+    assert!(!cont.labels().contains(&"a".to_string()));
+    assert!(cont.labels().contains(&"b".to_string()));
+}
+
+#[test]
+fn forget_definition() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("a b"), "c");
+    assert_eq!(cont.execute("forget c"), "");
+    assert_eq!(cont.execute("a b"), "a b");
+    assert_eq!(cont.execute("let c := a b"), "");
+}
+
+#[test]
+fn forget_used_concept() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(
+        cont.execute("forget a"),
+        ZiaError::ForgettingUsedConcept.to_string()
+    );
+    assert_eq!(cont.execute("let d -> c"), "");
+    assert_eq!(
+        cont.execute("forget c"),
+        ZiaError::ForgettingUsedConcept.to_string()
+    );
+}
+
+#[test]
+fn forget_concrete() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(
+        cont.execute("forget true"),
+        ZiaError::ForgettingConcrete.to_string()
+    );
+}
+
+#[test]
+fn forget_unused_symbol() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("forget a"), ZiaError::UnusedSymbol.to_string());
+}
+
+proptest! {
+    // Forgetting a nested definition should leave the context able to redefine it
+    #[test]
+    fn forget_nested_definition(a in "a|b|c", b in "a|b|c") {
+        prop_assume!(a != b);
+        let mut cont = NEW_CONTEXT.clone();
+        assert_eq!(cont.execute(&format!("let d := {} e", a)), "");
+        assert_eq!(cont.execute(&format!("let f := d {}", b)), "");
+        assert_eq!(cont.execute("forget f"), "");
+        assert_eq!(cont.execute(&format!("d {}", b)), format!("d {}", b));
+        assert_eq!(cont.execute(&format!("let f := d {}", b)), "");
+        assert_eq!(cont.execute(&format!("d {}", b)), "f");
+    }
+}
@@ -0,0 +1,66 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use std::sync::{Arc, Mutex};
+use zia::NEW_CONTEXT;
+
+#[test]
+fn forks_are_independent() {
+    let mut parent = NEW_CONTEXT.fork();
+    assert_eq!(parent.execute("let (a b) -> c"), "");
+    let mut child = parent.fork();
+    let mut sibling = parent.fork();
+    assert_eq!(child.execute("let c -> d"), "");
+    assert_eq!(sibling.execute("let (a b) -> a b"), "");
+    assert_eq!(parent.execute("let e := a b"), "");
+    assert_eq!(parent.execute("a b"), "c");
+    assert_eq!(parent.execute("c"), "c");
+    assert_eq!(parent.execute("definition_of e"), "a b");
+    assert_eq!(child.execute("a b"), "d");
+    assert_eq!(child.execute("definition_of e"), "definition_of e");
+    assert_eq!(sibling.execute("a b"), "a b");
+    assert_eq!(sibling.execute("definition_of e"), "definition_of e");
+    assert_eq!(parent.check_integrity(), Vec::<String>::new());
+    assert_eq!(child.check_integrity(), Vec::<String>::new());
+    assert_eq!(sibling.check_integrity(), Vec::<String>::new());
+}
+
+#[test]
+fn forks_can_be_compacted() {
+    let mut parent = NEW_CONTEXT.fork();
+    assert_eq!(parent.execute("let (a b) -> c"), "");
+    assert_eq!(parent.execute("let (a b) -> a b"), "");
+    assert_eq!(parent.execute("let d -> e"), "");
+    let mut child = parent.fork();
+    assert!(child.collect_garbage(true).unwrap() > 0);
+    assert_eq!(child.execute("d"), "e");
+    assert_eq!(parent.execute("d"), "e");
+    assert!(parent.diff_semantic(&child).unwrap().is_empty());
+}
+
+#[test]
+fn forks_do_not_call_observers() {
+    let mut parent = NEW_CONTEXT.fork();
+    let calls = Arc::new(Mutex::new(0));
+    let observed = calls.clone();
+    parent.subscribe(move |_| *observed.lock().unwrap() += 1);
+    let mut child = parent.fork();
+    assert_eq!(child.execute("let a -> b"), "");
+    assert_eq!(*calls.lock().unwrap(), 0);
+    assert_eq!(parent.execute("let a -> c"), "");
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
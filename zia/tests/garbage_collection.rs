@@ -0,0 +1,68 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+#[macro_use]
+extern crate proptest;
+extern crate zia;
+
+use zia::NEW_CONTEXT;
+
+#[test]
+fn new_context_has_no_garbage() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.collect_garbage(false), Ok(0));
+    assert_eq!(cont.check_integrity(), Vec::<String>::new());
+}
+
+#[test]
+fn removed_reduction_leaves_garbage() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let (a b) -> a b"), "");
+    assert!(cont.collect_garbage(false).unwrap() > 0);
+    assert_eq!(cont.collect_garbage(false), Ok(0));
+    assert_eq!(cont.execute("a b"), "a b");
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("a b"), "c");
+}
+
+#[test]
+fn relabelling_leaves_garbage() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let d := c"), "");
+    assert!(cont.collect_garbage(false).unwrap() > 0);
+    assert_eq!(cont.execute("a b"), "d");
+    assert_eq!(cont.execute("label_of (a b)"), "'d'");
+}
+
+proptest! {
+    // Compacting the context should not change the semantics of its concepts
+    #[test]
+    fn compaction_keeps_semantics(a in "a|b", b in "c|f", c in "g|h") {
+        let mut cont = NEW_CONTEXT.clone();
+        assert_eq!(cont.execute(&format!("let ({} {}) -> {}", a, b, c)), "");
+        assert_eq!(cont.execute(&format!("let ({} {}) -> {} {}", a, b, a, b)), "");
+        assert_eq!(cont.execute(&format!("let ({} {}) -> {}", b, c, a)), "");
+        assert_eq!(cont.execute(&format!("let d := {} {}", c, a)), "");
+        cont.collect_garbage(true).unwrap();
+        prop_assert_eq!(cont.check_integrity(), Vec::<String>::new());
+        assert_eq!(cont.execute(&format!("{} {}", a, b)), format!("{} {}", a, b));
+        assert_eq!(cont.execute(&format!("{} {}", b, c)), a);
+        assert_eq!(cont.execute(&format!("{} {}", c, a)), "d");
+        assert_eq!(cont.execute("let e -> d"), "");
+        assert_eq!(cont.execute("e"), "d");
+    }
+}
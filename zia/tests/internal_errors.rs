@@ -0,0 +1,67 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use std::{
+    env::temp_dir,
+    fs::{read_to_string, remove_file, write},
+    path::PathBuf,
+    process,
+};
+use zia::{Context, NEW_CONTEXT};
+
+fn temp_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("zia-{}-{}", process::id(), name))
+}
+
+#[test]
+fn inconsistent_concepts_leave_context_unchanged() {
+    let path = temp_path("inconsistent.snapshot");
+    let unchanged = temp_path("unchanged.snapshot");
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let a -> b"), "");
+    let a = cont.inspect("a").unwrap().unwrap().id;
+    cont.save_snapshot(&path).unwrap();
+    // Make `a` reduce to a concept that doesn't exist
+    let prefix = format!("insert {} ", a);
+    let snapshot: String = read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            if line.starts_with(&prefix) {
+                let mut fields = line.rsplitn(2, ' ');
+                fields.next();
+                format!("{} 9999\n", fields.next().unwrap())
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect();
+    write(&path, snapshot).unwrap();
+    let mut cont = Context::load_snapshot(&path).unwrap();
+    // Forgetting `a` changes other concepts before finding that its reduction doesn't exist
+    assert_eq!(
+        cont.try_execute("forget a").unwrap_err().variant_name(),
+        "Internal"
+    );
+    cont.save_snapshot(&unchanged).unwrap();
+    assert_eq!(
+        read_to_string(&unchanged).unwrap(),
+        read_to_string(&path).unwrap()
+    );
+    remove_file(path).unwrap();
+    remove_file(unchanged).unwrap();
+}
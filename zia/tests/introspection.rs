@@ -0,0 +1,104 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use zia::{ConceptKind, NEW_CONTEXT};
+
+#[test]
+fn definition_of_concept() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let a -> d"), "");
+    assert_eq!(cont.execute("definition_of c"), "a b");
+    // A concept without a definition cannot be inspected this way
+    assert_eq!(cont.execute("definition_of b"), "definition_of b");
+}
+
+#[test]
+fn reduction_of_concept() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c d"), "");
+    assert_eq!(cont.execute("let (c d) -> e"), "");
+    // Only the direct reduction is shown, not the normal form
+    assert_eq!(cont.execute("reduction_of (a b)"), "c d");
+    assert_eq!(cont.execute("reduction_of e"), "reduction_of e");
+}
+
+#[test]
+fn reduces_from_concept() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let d -> c"), "");
+    assert_eq!(cont.execute("reduces_from c"), "a b, d");
+    assert_eq!(cont.execute("reduces_from a"), "reduces_from a");
+}
+
+#[test]
+fn labels_of_context() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert!(cont.labels().contains(&"let".to_string()));
+    assert!(cont.labels().contains(&"->".to_string()));
+    assert_eq!(cont.execute("let (a b) -> ∑"), "");
+    assert!(cont.labels().contains(&"∑".to_string()));
+    assert!(!cont.labels().contains(&"a b".to_string()));
+    let labels = cont.labels();
+    let mut sorted = labels.clone();
+    sorted.sort();
+    assert_eq!(labels, sorted);
+}
+
+#[test]
+fn concrete_labels_of_context() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let a -> b"), "");
+    let concrete = cont.concrete_labels();
+    assert!(concrete.contains(&"let".to_string()));
+    assert!(concrete.contains(&":=".to_string()));
+    assert!(!concrete.contains(&"a".to_string()));
+    assert!(cont.labels().contains(&"a".to_string()));
+}
+
+#[test]
+fn inspect_concept() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let c -> d"), "");
+    assert_eq!(cont.execute("let d -> e"), "");
+    assert_eq!(cont.execute("let f -> d"), "");
+    let c = cont.inspect("c").unwrap().unwrap();
+    assert_eq!(c.kind, ConceptKind::Abstract);
+    assert_eq!(c.definition, Some("a b".to_string()));
+    assert_eq!(c.reduction, Some("d".to_string()));
+    assert!(c.reduces_from.is_empty());
+    assert_eq!(c.normal_form, "e");
+    assert_eq!(c.associativity, "right");
+    assert_eq!(c.precedence, "default");
+    let d = cont.inspect("d").unwrap().unwrap();
+    assert_eq!(d.reduces_from, ["c", "f"]);
+    assert_eq!(d.definition, None);
+    let a = cont.inspect("a").unwrap().unwrap();
+    assert_eq!(a.lefthand_of, ["c"]);
+    let b = cont.inspect("b").unwrap().unwrap();
+    assert_eq!(b.righthand_of, ["c"]);
+    // How labels are stored is left out
+    assert!(c.righthand_of.is_empty());
+    assert_eq!(cont.inspect("(a b)").unwrap().unwrap().id, c.id);
+    assert_eq!(
+        cont.inspect("let").unwrap().unwrap().kind,
+        ConceptKind::Concrete
+    );
+    assert_eq!(cont.inspect("g"), Ok(None));
+}
@@ -0,0 +1,159 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use std::{
+    env::temp_dir,
+    fs::{read_to_string, remove_file, OpenOptions},
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    process,
+};
+use zia::{Context, NEW_CONTEXT};
+
+fn temp_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("zia-{}-{}", process::id(), name))
+}
+
+fn remove(paths: &[&PathBuf]) {
+    for path in paths {
+        remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn recover_journalled_changes() {
+    let snapshot = temp_path("recover.snapshot");
+    let journal = temp_path("recover.journal");
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    cont.save_snapshot(&snapshot).unwrap();
+    cont.journal(&journal).unwrap();
+    assert_eq!(cont.execute("let d := a b"), "");
+    assert_eq!(cont.execute("let (_x_ e) -> _x_"), "");
+    assert_eq!(cont.execute("let (a b) -> a b"), "");
+    assert_eq!(cont.execute("let f -> g"), "");
+    assert_eq!(cont.execute("let f -> f"), "");
+    let mut recovered = Context::recover(&snapshot, &journal).unwrap();
+    assert!(cont.diff_semantic(&recovered).unwrap().is_empty());
+    assert_eq!(recovered.check_integrity(), Vec::<String>::new());
+    assert_eq!(recovered.execute("label_of (a b)"), "'d'");
+    assert_eq!(recovered.execute("h e"), "h");
+    assert_eq!(recovered.execute("let i -> j"), "");
+    assert_eq!(recovered.execute("i"), "j");
+    remove(&[&snapshot, &journal]);
+}
+
+#[test]
+fn recover_after_compaction() {
+    let snapshot = temp_path("compaction.snapshot");
+    let journal = temp_path("compaction.journal");
+    let mut cont = NEW_CONTEXT.clone();
+    cont.save_snapshot(&snapshot).unwrap();
+    cont.journal(&journal).unwrap();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let (a b) -> a b"), "");
+    assert!(cont.collect_garbage(true).unwrap() > 0);
+    assert_eq!(cont.execute("let (d e) -> f"), "");
+    let mut recovered = Context::recover(&snapshot, &journal).unwrap();
+    assert!(cont.diff_semantic(&recovered).unwrap().is_empty());
+    assert_eq!(recovered.check_integrity(), Vec::<String>::new());
+    assert_eq!(recovered.execute("d e"), "f");
+    remove(&[&snapshot, &journal]);
+}
+
+#[test]
+fn unfinished_commit_is_ignored() {
+    let snapshot = temp_path("unfinished.snapshot");
+    let journal = temp_path("unfinished.journal");
+    let mut cont = NEW_CONTEXT.clone();
+    cont.save_snapshot(&snapshot).unwrap();
+    cont.journal(&journal).unwrap();
+    assert_eq!(cont.execute("let a -> b"), "");
+    let committed = read_to_string(&journal).unwrap();
+    assert_eq!(cont.execute("let c -> d"), "");
+    let written = read_to_string(&journal).unwrap();
+    let crashed = &written[..written.len() - "end\n".len()];
+    assert!(crashed.len() > committed.len());
+    OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(&journal)
+        .unwrap()
+        .write_all(crashed.as_bytes())
+        .unwrap();
+    let mut recovered = Context::recover(&snapshot, &journal).unwrap();
+    assert_eq!(recovered.execute("a"), "b");
+    assert_eq!(recovered.execute("c"), "c");
+    assert_eq!(recovered.check_integrity(), Vec::<String>::new());
+    remove(&[&snapshot, &journal]);
+}
+
+#[test]
+fn clones_do_not_write_to_the_journal() {
+    let snapshot = temp_path("clones.snapshot");
+    let journal = temp_path("clones.journal");
+    let mut cont = NEW_CONTEXT.clone();
+    cont.save_snapshot(&snapshot).unwrap();
+    cont.journal(&journal).unwrap();
+    let mut clone = cont.clone();
+    assert_eq!(clone.execute("let a -> b"), "");
+    assert_eq!(cont.fork().execute("let c -> d"), "");
+    assert_eq!(cont.execute("let e -> f"), "");
+    let mut recovered = Context::recover(&snapshot, &journal).unwrap();
+    assert_eq!(recovered.execute("a"), "a");
+    assert_eq!(recovered.execute("c"), "c");
+    assert_eq!(recovered.execute("e"), "f");
+    remove(&[&snapshot, &journal]);
+}
+
+#[test]
+fn files_of_the_wrong_kind_are_rejected() {
+    let snapshot = temp_path("wrong.snapshot");
+    let journal = temp_path("wrong.journal");
+    let mut cont = NEW_CONTEXT.clone();
+    cont.save_snapshot(&snapshot).unwrap();
+    cont.journal(&journal).unwrap();
+    assert_eq!(
+        Context::recover(&journal, &snapshot).err().map(|e| e.kind()),
+        Some(ErrorKind::InvalidData)
+    );
+    remove(&[&snapshot, &journal]);
+}
+
+#[test]
+fn recover_without_journal() {
+    let snapshot = temp_path("without.snapshot");
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let a -> b"), "");
+    cont.save_snapshot(&snapshot).unwrap();
+    let mut recovered =
+        Context::recover(&snapshot, temp_path("missing.journal")).unwrap();
+    assert_eq!(recovered.execute("a"), "b");
+    remove(&[&snapshot]);
+}
+
+#[test]
+fn load_snapshot() {
+    let snapshot = temp_path("load.snapshot");
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    cont.save_snapshot(&snapshot).unwrap();
+    let mut loaded = Context::load_snapshot(&snapshot).unwrap();
+    assert!(cont.diff_semantic(&loaded).unwrap().is_empty());
+    assert_eq!(loaded.execute("a b"), "c");
+    remove(&[&snapshot]);
+}
@@ -0,0 +1,180 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use zia::{MergeConflict, ZiaError, NEW_CONTEXT};
+
+#[test]
+fn changes_from_both_sides_are_combined() {
+    let mut base = NEW_CONTEXT.clone();
+    assert_eq!(base.execute("let a -> b"), "");
+    assert_eq!(base.execute("let c := d e"), "");
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let f -> g"), "");
+    assert_eq!(theirs.execute("let (h i) -> j"), "");
+    assert_eq!(theirs.execute("let k := l m"), "");
+    assert_eq!(theirs.execute("let a -> a"), "");
+    assert_eq!(theirs.execute("let c := n o"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(conflicts, vec![]);
+    assert_eq!(merged.execute("a"), "a");
+    assert_eq!(merged.execute("f"), "g");
+    assert_eq!(merged.execute("h i"), "j");
+    assert_eq!(merged.execute("label_of (l m)"), "'k'");
+    assert_eq!(merged.execute("label_of (n o)"), "'c'");
+    assert_eq!(merged.execute("label_of (d e)"), "'d e'");
+}
+
+#[test]
+fn same_changes_on_both_sides_do_not_conflict() {
+    let base = NEW_CONTEXT.clone();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let (a b) -> c"), "");
+    assert_eq!(theirs.execute("let (a b) -> c"), "");
+    let (merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(conflicts, vec![]);
+    assert!(merged.diff_semantic(&ours).unwrap().is_empty());
+}
+
+#[test]
+fn different_reductions_conflict() {
+    let mut base = NEW_CONTEXT.clone();
+    assert_eq!(base.execute("let a -> b"), "");
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let a -> c"), "");
+    assert_eq!(theirs.execute("let a -> a"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(
+        conflicts,
+        vec![MergeConflict::Reduction {
+            syntax: "a".to_string(),
+            ours: Some("c".to_string()),
+            theirs: None,
+        }]
+    );
+    assert_eq!(merged.execute("a"), "c");
+}
+
+#[test]
+fn same_label_for_different_concepts_conflicts() {
+    let base = NEW_CONTEXT.clone();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let j := h i"), "");
+    assert_eq!(theirs.execute("let j := k l"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(
+        conflicts,
+        vec![MergeConflict::Label {
+            label: "j".to_string(),
+            ours: Some("h i".to_string()),
+            theirs: Some("k l".to_string()),
+        }]
+    );
+    assert_eq!(merged.execute("label_of (h i)"), "'j'");
+}
+
+#[test]
+fn combined_cyclic_reductions_conflict() {
+    let base = NEW_CONTEXT.clone();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let a -> b"), "");
+    assert_eq!(theirs.execute("let b -> a"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(
+        conflicts,
+        vec![MergeConflict::Rejected {
+            command: "let b -> a".to_string(),
+            error: ZiaError::CyclicReduction,
+        }]
+    );
+    assert_eq!(merged.execute("a"), "b");
+    assert_eq!(merged.execute("b"), "b");
+    assert_eq!(merged.check_integrity(), Vec::<String>::new());
+}
+
+#[test]
+fn relabelled_concepts_keep_their_rules() {
+    let mut base = NEW_CONTEXT.clone();
+    assert_eq!(base.execute("let a -> b"), "");
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let (a x) -> y"), "");
+    assert_eq!(theirs.execute("let c := a"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(conflicts, vec![]);
+    assert_eq!(merged.execute("c"), "b");
+    assert_eq!(merged.execute("c x"), "y");
+    assert_eq!(merged.execute("a"), "a");
+    assert_eq!(merged.check_integrity(), Vec::<String>::new());
+}
+
+#[test]
+fn different_labels_for_the_same_concept_conflict() {
+    let mut base = NEW_CONTEXT.clone();
+    assert_eq!(base.execute("let a -> b"), "");
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let c := a"), "");
+    assert_eq!(theirs.execute("let d := a"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(
+        conflicts,
+        vec![MergeConflict::Relabel {
+            concept: "a".to_string(),
+            ours: Some("c".to_string()),
+            theirs: Some("d".to_string()),
+        }]
+    );
+    assert_eq!(merged.execute("c"), "b");
+    assert_eq!(merged.execute("d"), "d");
+}
+
+#[test]
+fn rules_with_the_same_variables_are_combined() {
+    let base = NEW_CONTEXT.clone();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let (_x_ y) -> _x_"), "");
+    assert_eq!(theirs.execute("let (_x_ z) -> z"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(conflicts, vec![]);
+    assert_eq!(merged.execute("a y"), "a");
+    assert_eq!(merged.execute("a z"), "z");
+    assert_eq!(merged.check_integrity(), Vec::<String>::new());
+}
+
+#[test]
+fn forgotten_concepts_are_kept_if_our_side_uses_them() {
+    let mut base = NEW_CONTEXT.clone();
+    assert_eq!(base.execute("let a -> b"), "");
+    assert_eq!(base.execute("let c -> d"), "");
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    assert_eq!(ours.execute("let (c e) -> f"), "");
+    assert_eq!(theirs.execute("forget a"), "");
+    assert_eq!(theirs.execute("forget c"), "");
+    let (mut merged, conflicts) = base.merge(&ours, &theirs).unwrap();
+    assert_eq!(conflicts, vec![]);
+    assert!(!merged.labels().contains(&"a".to_string()));
+    assert_eq!(merged.execute("c"), "d");
+    assert_eq!(merged.execute("c e"), "f");
+    assert_eq!(merged.check_integrity(), Vec::<String>::new());
+}
@@ -0,0 +1,91 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use std::sync::{Arc, Mutex};
+use zia::{ChangeSummary, Context, NEW_CONTEXT};
+
+fn observed_context() -> (Context, Arc<Mutex<Vec<ChangeSummary>>>) {
+    let mut cont = NEW_CONTEXT.clone();
+    let summaries = Arc::new(Mutex::new(Vec::new()));
+    let observed = summaries.clone();
+    cont.subscribe(move |summary| {
+        observed.lock().unwrap().push(summary.clone())
+    });
+    (cont, summaries)
+}
+
+#[test]
+fn new_reduction_rule() {
+    let (mut cont, summaries) = observed_context();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    let summaries = summaries.lock().unwrap();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].labels_added(), ["a", "b", "c"]);
+    assert!(summaries[0].labels_removed().is_empty());
+    assert_eq!(summaries[0].reductions_changed(), ["a b"]);
+    assert!(summaries[0].definitions_changed().is_empty());
+}
+
+#[test]
+fn changed_definition() {
+    let (mut cont, summaries) = observed_context();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let c := c"), "");
+    let summaries = summaries.lock().unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0].definitions_changed(), ["c"]);
+    assert_eq!(summaries[1].labels_removed(), ["a", "b", "c"]);
+    assert_eq!(summaries[1].definitions_changed(), ["c"]);
+    assert!(summaries[1].reductions_changed().is_empty());
+}
+
+#[test]
+fn unchanged_context() {
+    let (mut cont, summaries) = observed_context();
+    assert_eq!(cont.execute("let a -> b"), "");
+    assert_eq!(cont.execute("a"), "b");
+    assert_eq!(
+        cont.execute("let a -> b"),
+        "That reduction rule already exists."
+    );
+    assert_eq!(cont.collect_garbage(true), Ok(0));
+    assert_eq!(summaries.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn compaction_does_not_change_rules() {
+    let (mut cont, summaries) = observed_context();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let (a b) -> a b"), "");
+    assert_eq!(cont.execute("let (d e) -> f"), "");
+    let len = summaries.lock().unwrap().len();
+    assert!(cont.collect_garbage(true).unwrap() > 0);
+    assert_eq!(cont.execute("d e"), "f");
+    let summaries = summaries.lock().unwrap();
+    assert!(summaries[len..]
+        .iter()
+        .all(|summary| summary.reductions_changed().is_empty()
+            && summary.definitions_changed().is_empty()));
+}
+
+#[test]
+fn clones_do_not_call_observers() {
+    let (cont, summaries) = observed_context();
+    let mut clone = cont.clone();
+    assert_eq!(clone.execute("let a -> b"), "");
+    assert!(summaries.lock().unwrap().is_empty());
+}
@@ -143,3 +143,52 @@ proptest! {
         );
     }
 }
+
+// Only a single reduction step is taken by `step`
+#[test]
+fn single_step() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c d"), "");
+    assert_eq!(cont.execute("let (c d) -> e"), "");
+    assert_eq!(cont.execute("step (a b)"), "c d");
+    assert_eq!(cont.execute("step (c d)"), "e");
+    assert_eq!(cont.execute("step e"), "e");
+}
+
+// `steps` takes at most the given number of reduction steps
+#[test]
+fn multiple_steps() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c d"), "");
+    assert_eq!(cont.execute("let (c d) -> e"), "");
+    assert_eq!(cont.execute("let e -> f"), "");
+    assert_eq!(cont.execute("steps 0 (a b)"), "a b");
+    assert_eq!(cont.execute("steps 2 (a b)"), "e");
+    assert_eq!(cont.execute("steps 5 (a b)"), "f");
+}
+
+// `steps` needs a number of steps that can be counted
+#[test]
+fn invalid_step_counts() {
+    let mut cont = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let a -> b"), "");
+    assert_eq!(
+        cont.try_execute("steps -1 a"),
+        Err(ZiaError::InvalidStepCount {
+            count: "-1".to_string()
+        })
+    );
+    assert_eq!(
+        cont.try_execute("steps x a"),
+        Err(ZiaError::InvalidStepCount {
+            count: "x".to_string()
+        })
+    );
+    assert_eq!(
+        cont.execute("steps (1 2) a"),
+        ZiaError::InvalidStepCount {
+            count: "1 2".to_string()
+        }
+        .to_string()
+    );
+}
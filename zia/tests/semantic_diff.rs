@@ -0,0 +1,82 @@
+//  Library for the Zia programming language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+extern crate zia;
+
+use zia::{SemanticDiff, NEW_CONTEXT};
+
+#[test]
+fn order_of_commands_does_not_matter() {
+    let mut cont = NEW_CONTEXT.clone();
+    let mut other = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let d := e f"), "");
+    assert_eq!(other.execute("let d := e f"), "");
+    assert_eq!(other.execute("let (a b) -> c"), "");
+    assert!(cont.diff_semantic(&other).unwrap().is_empty());
+    assert!(NEW_CONTEXT.diff_semantic(&NEW_CONTEXT).unwrap().is_empty());
+}
+
+#[test]
+fn reduction_rule_differences() {
+    let mut cont = NEW_CONTEXT.clone();
+    let mut other = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let x -> y"), "");
+    assert_eq!(other.execute("let (a b) -> d"), "");
+    assert_eq!(other.execute("let (_x_ f) -> _x_"), "");
+    assert_eq!(
+        cont.diff_semantic(&other),
+        Ok(SemanticDiff {
+            added: vec!["let (_x_ f) -> _x_".to_string()],
+            removed: vec!["let x -> x".to_string()],
+            changed: vec!["let (a b) -> d".to_string()],
+        })
+    );
+}
+
+#[test]
+fn definition_differences() {
+    let mut cont = NEW_CONTEXT.clone();
+    let mut other = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let c := a b"), "");
+    assert_eq!(cont.execute("let g := h i"), "");
+    assert_eq!(other.execute("let c := a d"), "");
+    assert_eq!(other.execute("let j := k l"), "");
+    assert_eq!(
+        cont.diff_semantic(&other),
+        Ok(SemanticDiff {
+            added: vec!["let j := k l".to_string()],
+            removed: vec!["let g := g".to_string()],
+            changed: vec!["let c := a d".to_string()],
+        })
+    );
+}
+
+#[test]
+fn commands_make_contexts_agree() {
+    let mut cont = NEW_CONTEXT.clone();
+    let mut other = NEW_CONTEXT.clone();
+    assert_eq!(cont.execute("let (a b) -> c"), "");
+    assert_eq!(cont.execute("let x -> y"), "");
+    assert_eq!(other.execute("let (a b) -> d"), "");
+    assert_eq!(other.execute("let z := x w"), "");
+    let diff = cont.diff_semantic(&other).unwrap();
+    for command in diff.removed.iter().chain(&diff.changed).chain(&diff.added) {
+        assert_eq!(cont.execute(command), "");
+    }
+    assert!(cont.diff_semantic(&other).unwrap().is_empty());
+    assert_eq!(cont.execute("a b"), "d");
+}
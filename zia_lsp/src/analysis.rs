@@ -0,0 +1,297 @@
+// Zia Language Server: Editor Support for the Zia Programming Language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range,
+};
+use std::collections::HashMap;
+use zia::{is_variable, syntax::is_unbalanced, Context};
+
+/// What is known about a script once each of its commands has been executed in a new context.
+pub struct Analysis {
+    /// The context after the last command.
+    pub context: Context,
+    /// An error for each command that failed.
+    pub diagnostics: Vec<Diagnostic>,
+    /// The range of the last `let` command that defined each label with `:=` or gave it a reduction with `->`.
+    pub definitions: HashMap<String, Range>,
+}
+
+impl Analysis {
+    /// Executes each command of `script` in order. As in izia scripts, blank lines are skipped and a command continues on the following lines until its parentheses are balanced. Meta-commands, which start with `:`, are skipped as they are not part of the language.
+    pub fn new(script: &str) -> Self {
+        let mut analysis = Self {
+            context: Context::new_without_logging(),
+            diagnostics: Vec::new(),
+            definitions: HashMap::new(),
+        };
+        let mut command = String::new();
+        let mut start = 0;
+        let lines: Vec<&str> = script.lines().collect();
+        for (index, line) in lines.iter().enumerate() {
+            if command.is_empty() {
+                if line.trim().is_empty() || line.trim_start().starts_with(':')
+                {
+                    continue;
+                }
+                start = index;
+            } else {
+                command.push(' ');
+            }
+            command.push_str(line);
+            if is_unbalanced(&command) && index + 1 < lines.len() {
+                continue;
+            }
+            let range = Range::new(
+                Position::new(start as u32, 0),
+                Position::new(index as u32, utf16_len(line)),
+            );
+            analysis.execute(&command, range);
+            command.clear();
+        }
+        analysis
+    }
+
+    fn execute(&mut self, command: &str, range: Range) {
+        match self.context.try_execute(command) {
+            Ok(_) => {
+                for label in defined_labels(command) {
+                    self.definitions.insert(label, range);
+                }
+            },
+            Err(error) => self.diagnostics.push(Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(
+                    error.variant_name().to_string(),
+                )),
+                source: Some("zia".to_string()),
+                message: error.to_string(),
+                ..Diagnostic::default()
+            }),
+        }
+    }
+
+    /// Describes what the concept labelled `label` reduces to and is defined as, in Markdown.
+    pub fn hover(&self, label: &str) -> Option<String> {
+        let details = self.context.inspect(label).ok()??;
+        let mut text = format!("`{}`", label);
+        if let Some(definition) = details.definition {
+            text += &format!("\n\ndefined as `{}`", definition);
+        }
+        if let Some(reduction) = details.reduction {
+            text += &format!("\n\nreduces to `{}`", reduction);
+        }
+        if details.normal_form != label {
+            text += &format!("\n\nnormal form `{}`", details.normal_form);
+        }
+        Some(text)
+    }
+}
+
+/// Returns the symbol at `position` in `text`, or just before it, if there is one.
+pub fn symbol_at(text: &str, position: Position) -> Option<String> {
+    let line = text.lines().nth(position.line as usize)?;
+    let mut symbol = String::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for c in line.chars() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if !symbol.is_empty()
+                && start <= position.character
+                && position.character <= offset
+            {
+                return Some(symbol);
+            }
+            symbol.clear();
+        } else {
+            if symbol.is_empty() {
+                start = offset;
+            }
+            symbol.push(c);
+        }
+        offset += c.len_utf16() as u32;
+    }
+    if !symbol.is_empty() && start <= position.character {
+        Some(symbol)
+    } else {
+        None
+    }
+}
+
+/// The labels left of the `:=` or `->` of a `let` command, such as `a` and `b` in `let a b -> c`. Variables are left out as they stand for any expression.
+fn defined_labels(command: &str) -> Vec<String> {
+    let mut symbols: Vec<(String, usize)> = Vec::new();
+    let mut symbol = String::new();
+    let mut depth: usize = 0;
+    for c in command.chars().chain(Some(' ')) {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if !symbol.is_empty() {
+                symbols.push((symbol.clone(), depth));
+                symbol.clear();
+            }
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+        } else {
+            symbol.push(c);
+        }
+    }
+    if symbols.first() != Some(&("let".to_string(), 0)) {
+        return Vec::new();
+    }
+    // The first operator at the top level with something on either side
+    let operator = symbols.iter().enumerate().position(|(index, (s, d))| {
+        *d == 0
+            && (s == ":=" || s == "->")
+            && index > 1
+            && index + 1 < symbols.len()
+    });
+    operator.map_or_else(Vec::new, |operator| {
+        symbols[1..operator]
+            .iter()
+            .map(|(s, _)| s.clone())
+            .filter(|s| !is_variable(s))
+            .collect()
+    })
+}
+
+/// The length of `line` in UTF-16 code units, which is how positions are counted.
+fn utf16_len(line: &str) -> u32 {
+    line.encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{symbol_at, Analysis};
+    use lsp_types::{NumberOrString, Position, Range};
+
+    fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    #[test]
+    fn symbols_at_word_boundaries() {
+        let text = "let ab -> c";
+        let symbol = |character| symbol_at(text, Position::new(0, character));
+        assert_eq!(symbol(0), Some("let".to_string()));
+        assert_eq!(symbol(3), Some("let".to_string()));
+        assert_eq!(symbol(4), Some("ab".to_string()));
+        assert_eq!(symbol(6), Some("ab".to_string()));
+        assert_eq!(symbol(7), Some("->".to_string()));
+        assert_eq!(symbol(11), Some("c".to_string()));
+    }
+
+    #[test]
+    fn symbols_next_to_parentheses() {
+        let text = "(a b)";
+        let symbol = |character| symbol_at(text, Position::new(0, character));
+        assert_eq!(symbol(0), None);
+        assert_eq!(symbol(1), Some("a".to_string()));
+        assert_eq!(symbol(4), Some("b".to_string()));
+        assert_eq!(symbol(5), None);
+    }
+
+    #[test]
+    fn symbols_on_later_lines() {
+        let text = "let a -> b\n\n😀 d";
+        assert_eq!(symbol_at(text, Position::new(1, 0)), None);
+        assert_eq!(
+            symbol_at(text, Position::new(2, 0)),
+            Some("😀".to_string())
+        );
+        // The emoji is two UTF-16 code units long
+        assert_eq!(symbol_at(text, Position::new(2, 3)), Some("d".to_string()));
+        assert_eq!(symbol_at(text, Position::new(3, 0)), None);
+    }
+
+    #[test]
+    fn failed_commands_are_diagnosed() {
+        let analysis =
+            Analysis::new("let a -> b\n\nlet a -> b\n:help\nlet (c\nd");
+        let diagnosed: Vec<(Range, Option<NumberOrString>)> = analysis
+            .diagnostics
+            .into_iter()
+            .map(|d| (d.range, d.code))
+            .collect();
+        assert_eq!(
+            diagnosed,
+            [
+                (
+                    range((2, 0), (2, 10)),
+                    Some(NumberOrString::String(
+                        "RedundantReduction".to_string()
+                    ))
+                ),
+                (
+                    range((4, 0), (5, 1)),
+                    Some(NumberOrString::String("MissingSymbol".to_string()))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn definitions_are_let_commands() {
+        let analysis = Analysis::new(
+            "let a -> b\nc := d\nlet e := (a\n  c)\nlet _x_ f -> _x_",
+        );
+        assert_eq!(
+            analysis.definitions.get("a"),
+            Some(&range((0, 0), (0, 10)))
+        );
+        assert_eq!(analysis.definitions.get("b"), None);
+        assert_eq!(analysis.definitions.get("c"), None);
+        assert_eq!(analysis.definitions.get("e"), Some(&range((2, 0), (3, 4))));
+        assert_eq!(
+            analysis.definitions.get("f"),
+            Some(&range((4, 0), (4, 16)))
+        );
+        assert_eq!(analysis.definitions.get("_x_"), None);
+    }
+
+    #[test]
+    fn redefinitions_move_the_definition() {
+        let analysis =
+            Analysis::new("let a -> b\nlet a -> c\nlet a -> c\nlet d := a");
+        // The last command failed as the reduction was already there
+        assert_eq!(
+            analysis.definitions.get("a"),
+            Some(&range((1, 0), (1, 10)))
+        );
+        assert_eq!(
+            analysis.definitions.get("d"),
+            Some(&range((3, 0), (3, 10)))
+        );
+    }
+
+    #[test]
+    fn hover_describes_the_concept() {
+        let analysis = Analysis::new("let a -> b\nlet c := a d");
+        assert_eq!(
+            analysis.hover("a"),
+            Some("`a`\n\nreduces to `b`\n\nnormal form `b`".to_string())
+        );
+        assert_eq!(
+            analysis.hover("c"),
+            Some("`c`\n\ndefined as `a d`".to_string())
+        );
+        assert_eq!(analysis.hover("b"), Some("`b`".to_string()));
+        assert_eq!(analysis.hover("e"), None);
+    }
+}
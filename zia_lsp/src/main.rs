@@ -0,0 +1,313 @@
+// Zia Language Server: Editor Support for the Zia Programming Language.
+// Copyright (C) 2019 Charles Johnson
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//! # Language Server for Zia Scripts.
+
+extern crate lsp_server;
+extern crate lsp_types;
+extern crate serde_json;
+extern crate zia;
+
+mod analysis;
+
+use analysis::{symbol_at, Analysis};
+use lsp_server::{
+    Connection, ErrorCode, Message, Notification, Request, Response,
+};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Request as _},
+    CompletionItem, CompletionOptions, CompletionParams, CompletionResponse,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse,
+    Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+    MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use std::{collections::HashMap, error::Error};
+
+type ServerResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// The text of each open script and what is known about it.
+type Documents = HashMap<Url, (String, Analysis)>;
+
+fn main() -> ServerResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::FULL,
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        ..ServerCapabilities::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    let mut documents = Documents::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = respond(&documents, request)?;
+                connection.sender.send(Message::Response(response))?;
+            },
+            Message::Notification(notification) => {
+                if let Some(uri) = update(&mut documents, notification) {
+                    publish_diagnostics(&connection, &documents, uri)?;
+                }
+            },
+            Message::Response(_) => (),
+        }
+    }
+    // The writer thread only stops once the connection is dropped
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Keeps track of the scripts opened, changed and closed by the editor. Returns the script whose diagnostics need publishing, if there is one. Notifications with malformed parameters are logged and ignored.
+fn update(
+    documents: &mut Documents,
+    notification: Notification,
+) -> Option<Url> {
+    let Notification {
+        method,
+        params,
+    } = notification;
+    let result = match method.as_str() {
+        DidOpenTextDocument::METHOD => serde_json::from_value(params).map(
+            |params: DidOpenTextDocumentParams| {
+                let document = params.text_document;
+                let analysis = Analysis::new(&document.text);
+                documents
+                    .insert(document.uri.clone(), (document.text, analysis));
+                Some(document.uri)
+            },
+        ),
+        DidChangeTextDocument::METHOD => serde_json::from_value(params).map(
+            |params: DidChangeTextDocumentParams| {
+                // The whole text is sent as only full synchronisation is supported
+                let uri = params.text_document.uri;
+                params.content_changes.into_iter().last().map(|change| {
+                    let analysis = Analysis::new(&change.text);
+                    documents.insert(uri.clone(), (change.text, analysis));
+                    uri
+                })
+            },
+        ),
+        DidCloseTextDocument::METHOD => serde_json::from_value(params).map(
+            |params: DidCloseTextDocumentParams| {
+                documents.remove(&params.text_document.uri);
+                None
+            },
+        ),
+        _ => Ok(None),
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("Ignoring {} notification: {}", method, e);
+        None
+    })
+}
+
+fn publish_diagnostics(
+    connection: &Connection,
+    documents: &Documents,
+    uri: Url,
+) -> ServerResult<()> {
+    let diagnostics = documents
+        .get(&uri)
+        .map_or_else(Vec::new, |(_, analysis)| analysis.diagnostics.clone());
+    let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+    connection.sender.send(Message::Notification(Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        params,
+    )))?;
+    Ok(())
+}
+
+/// Answers hover, go-to-definition and completion requests. Requests with malformed parameters get an `InvalidParams` error and other requests a `MethodNotFound` error.
+fn respond(documents: &Documents, request: Request) -> ServerResult<Response> {
+    let result = match request.method.as_str() {
+        HoverRequest::METHOD => serde_json::from_value(request.params)
+            .map(|params| serde_json::to_value(hover(documents, params))),
+        GotoDefinition::METHOD => serde_json::from_value(request.params)
+            .map(|params| serde_json::to_value(definition(documents, params))),
+        Completion::METHOD => serde_json::from_value(request.params)
+            .map(|params| serde_json::to_value(completion(documents, params))),
+        _ => {
+            return Ok(Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unknown request {}", request.method),
+            ))
+        },
+    };
+    Ok(match result {
+        Ok(value) => Response::new_ok(request.id, value?),
+        Err(e) => Response::new_err(
+            request.id,
+            ErrorCode::InvalidParams as i32,
+            e.to_string(),
+        ),
+    })
+}
+
+fn hover(documents: &Documents, params: HoverParams) -> Option<Hover> {
+    let position = params.text_document_position_params;
+    documents
+        .get(&position.text_document.uri)
+        .and_then(|(text, analysis)| {
+            symbol_at(text, position.position)
+                .and_then(|symbol| analysis.hover(&symbol))
+        })
+        .map(|value| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+}
+
+fn definition(
+    documents: &Documents,
+    params: GotoDefinitionParams,
+) -> Option<GotoDefinitionResponse> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+    documents.get(&uri).and_then(|(text, analysis)| {
+        symbol_at(text, position)
+            .and_then(|symbol| analysis.definitions.get(&symbol))
+            .map(|range| {
+                GotoDefinitionResponse::Scalar(Location::new(
+                    uri.clone(),
+                    *range,
+                ))
+            })
+    })
+}
+
+fn completion(
+    documents: &Documents,
+    params: CompletionParams,
+) -> Option<CompletionResponse> {
+    let uri = params.text_document_position.text_document.uri;
+    documents.get(&uri).map(|(_, analysis)| {
+        CompletionResponse::Array(
+            analysis
+                .context
+                .labels()
+                .into_iter()
+                .map(|label| CompletionItem {
+                    label,
+                    ..CompletionItem::default()
+                })
+                .collect(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{respond, update, Documents};
+    use lsp_server::{ErrorCode, Notification, Request, RequestId};
+    use lsp_types::{
+        notification::{DidOpenTextDocument, Notification as _},
+        request::{HoverRequest, Request as _},
+    };
+    use serde_json::json;
+
+    fn error_code(documents: &Documents, request: Request) -> Option<i32> {
+        respond(documents, request).unwrap().error.map(|e| e.code)
+    }
+
+    fn open(text: Option<&str>) -> Notification {
+        let mut document = json!({
+            "uri": "file:///a.zia",
+            "languageId": "zia",
+            "version": 1,
+        });
+        if let Some(text) = text {
+            document["text"] = json!(text);
+        }
+        Notification::new(
+            DidOpenTextDocument::METHOD.to_string(),
+            json!({ "textDocument": document }),
+        )
+    }
+
+    #[test]
+    fn malformed_requests_have_invalid_params() {
+        let documents = Documents::new();
+        let request = Request::new(
+            RequestId::from(1),
+            HoverRequest::METHOD.to_string(),
+            json!({ "textDocument": 1 }),
+        );
+        assert_eq!(
+            error_code(&documents, request),
+            Some(ErrorCode::InvalidParams as i32)
+        );
+    }
+
+    #[test]
+    fn well_formed_requests_are_answered() {
+        let mut documents = Documents::new();
+        assert!(update(&mut documents, open(Some("let a -> b"))).is_some());
+        let request = Request::new(
+            RequestId::from(2),
+            HoverRequest::METHOD.to_string(),
+            json!({
+                "textDocument": { "uri": "file:///a.zia" },
+                "position": { "line": 0, "character": 4 },
+            }),
+        );
+        let response = respond(&documents, request).unwrap();
+        assert!(response.error.is_none());
+        assert_eq!(
+            response.result.unwrap()["contents"]["value"],
+            "`a`\n\nreduces to `b`\n\nnormal form `b`"
+        );
+    }
+
+    #[test]
+    fn unknown_requests_are_not_found() {
+        let documents = Documents::new();
+        let request = Request::new(
+            RequestId::from(3),
+            "zia/unknown".to_string(),
+            json!(null),
+        );
+        assert_eq!(
+            error_code(&documents, request),
+            Some(ErrorCode::MethodNotFound as i32)
+        );
+    }
+
+    #[test]
+    fn malformed_notifications_are_ignored() {
+        let mut documents = Documents::new();
+        assert_eq!(update(&mut documents, open(None)), None);
+        assert!(documents.is_empty());
+        let uri = update(&mut documents, open(Some("let a -> b")));
+        assert_eq!(uri.map(String::from), Some("file:///a.zia".to_string()));
+        assert_eq!(documents.len(), 1);
+    }
+}